@@ -1,3 +1,5 @@
+#![allow(deprecated)]
+
 use vec_arena::Arena;
 
 /// The null index, akin to null pointers.
@@ -60,7 +62,7 @@ impl<T> List<T> {
         let node = self.arena.insert(Node {
             prev: NULL,
             next: NULL,
-            value: value,
+            value,
         });
 
         let tail = self.tail;
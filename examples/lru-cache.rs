@@ -0,0 +1,160 @@
+#![allow(deprecated)]
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use vec_arena::Arena;
+
+/// The null index, akin to null pointers.
+///
+/// Just like a null pointer indicates an address no object is ever stored at,
+/// the null index indicates an index no object is ever stored at.
+///
+/// Number `!0` is the largest possible value representable by `usize`.
+const NULL: usize = !0;
+
+struct Entry<K, V> {
+    /// Previous (more recently used) entry in the recency list.
+    prev: usize,
+
+    /// Next (less recently used) entry in the recency list.
+    next: usize,
+
+    /// Key this entry is stored under.
+    key: K,
+
+    /// Actual value stored in entry.
+    value: V,
+}
+
+struct LruCache<K, V> {
+    /// This is where entries are stored.
+    arena: Arena<Entry<K, V>>,
+
+    /// Index of the entry stored under each key.
+    map: HashMap<K, usize>,
+
+    /// Maximum number of entries.
+    capacity: usize,
+
+    /// Most recently used entry.
+    head: usize,
+
+    /// Least recently used entry.
+    tail: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// Constructs a new, empty cache holding at most `capacity` entries.
+    fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        LruCache {
+            arena: Arena::with_capacity(capacity),
+            map: HashMap::with_capacity(capacity),
+            capacity,
+            head: NULL,
+            tail: NULL,
+        }
+    }
+
+    /// Returns the number of entries in the cache.
+    fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Links entries `a` and `b` together, so that `a` comes before `b` in the list.
+    fn link(&mut self, a: usize, b: usize) {
+        if a != NULL {
+            self.arena[a].next = b;
+        } else {
+            self.head = b;
+        }
+        if b != NULL {
+            self.arena[b].prev = a;
+        } else {
+            self.tail = a;
+        }
+    }
+
+    /// Unlinks the entry at `index` from the recency list.
+    fn unlink(&mut self, index: usize) {
+        let (prev, next) = (self.arena[index].prev, self.arena[index].next);
+        self.link(prev, next);
+    }
+
+    /// Links the entry at `index` to the front of the recency list.
+    fn push_front(&mut self, index: usize) {
+        let head = self.head;
+        self.arena[index].prev = NULL;
+        self.link(index, head);
+        self.head = index;
+    }
+
+    /// Returns the value stored under `key` and marks it as most recently used.
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let index = *self.map.get(key)?;
+        self.unlink(index);
+        self.push_front(index);
+        Some(&self.arena[index].value)
+    }
+
+    /// Inserts `value` under `key` and marks it as most recently used.
+    ///
+    /// If the cache was full, the least recently used entry is evicted and returned.
+    fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(&index) = self.map.get(&key) {
+            self.arena[index].value = value;
+            self.unlink(index);
+            self.push_front(index);
+            return None;
+        }
+
+        let mut evicted = None;
+        if self.len() == self.capacity {
+            let tail = self.tail;
+            self.unlink(tail);
+            let entry = self.arena.remove(tail).unwrap();
+            self.map.remove(&entry.key);
+            evicted = Some((entry.key, entry.value));
+        }
+
+        let index = self.arena.insert(Entry {
+            prev: NULL,
+            next: NULL,
+            key: key.clone(),
+            value,
+        });
+        self.map.insert(key, index);
+        self.push_front(index);
+        evicted
+    }
+}
+
+fn main() {
+    let mut cache = LruCache::new(3);
+
+    assert!(cache.insert("a", 1).is_none());
+    assert!(cache.insert("b", 2).is_none());
+    assert!(cache.insert("c", 3).is_none());
+
+    // The recency order is now [c, b, a].
+
+    assert!(cache.get(&"a") == Some(&1));
+
+    // The recency order is now [a, c, b].
+
+    assert!(cache.insert("d", 4) == Some(("b", 2)));
+    assert!(cache.get(&"b").is_none());
+
+    // The recency order is now [d, a, c].
+
+    assert!(cache.insert("c", 30).is_none());
+    assert!(cache.insert("e", 5) == Some(("a", 1)));
+
+    // The recency order is now [e, c, d].
+
+    assert!(cache.len() == 3);
+    assert!(cache.get(&"c") == Some(&30));
+    assert!(cache.get(&"d") == Some(&4));
+    assert!(cache.get(&"e") == Some(&5));
+}
@@ -0,0 +1,74 @@
+#![allow(deprecated)]
+
+use vec_arena::{Arena, PriorityQueue};
+
+fn main() {
+    // A small road network, with the nodes stored in an arena.
+    let mut nodes = Arena::new();
+    let names = ["a", "b", "c", "d", "e", "f", "g"];
+    let ids: Vec<usize> = names.iter().map(|name| nodes.insert(*name)).collect();
+
+    let edges: &[(usize, usize, u32)] = &[
+        (0, 1, 7),
+        (0, 2, 9),
+        (0, 5, 14),
+        (1, 2, 10),
+        (1, 3, 15),
+        (2, 3, 11),
+        (2, 5, 2),
+        (3, 4, 6),
+        (4, 5, 9),
+    ];
+    let mut adjacent = vec![Vec::new(); nodes.len()];
+    for &(a, b, weight) in edges {
+        adjacent[ids[a]].push((ids[b], weight));
+        adjacent[ids[b]].push((ids[a], weight));
+    }
+
+    // Shortest distances from node "a" using Dijkstra's algorithm. Every node gets a handle into
+    // the queue, so its distance can be lowered in place when a shorter path is found.
+    let mut queue = PriorityQueue::with_capacity(nodes.len());
+    let handles: Vec<usize> = ids
+        .iter()
+        .map(|&id| queue.push(if id == ids[0] { 0 } else { u32::MAX }, id))
+        .collect();
+    let mut dist = vec![None; nodes.len()];
+
+    while let Some((d, node)) = queue.pop() {
+        // The remaining nodes are unreachable.
+        if d == u32::MAX {
+            break;
+        }
+        dist[node] = Some(d);
+
+        for &(next, weight) in &adjacent[node] {
+            if dist[next].is_some() {
+                continue;
+            }
+            let candidate = d.saturating_add(weight);
+            if let Some((&current, _)) = queue.get(handles[next]) {
+                if candidate < current {
+                    queue.decrease_key(handles[next], candidate);
+                }
+            }
+        }
+    }
+
+    assert!(
+        dist == [
+            Some(0),
+            Some(7),
+            Some(9),
+            Some(20),
+            Some(20),
+            Some(11),
+            None
+        ]
+    );
+    for (id, name) in nodes.iter() {
+        match dist[id] {
+            Some(d) => println!("{}: {}", name, d),
+            None => println!("{}: unreachable", name),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(deprecated)]
+
 use vec_arena::Arena;
 
 /// The null index, akin to null pointers.
@@ -24,7 +26,7 @@ impl<T> Node<T> {
         Node {
             parent: NULL,
             children: [NULL, NULL],
-            value: value,
+            value,
         }
     }
 }
@@ -0,0 +1,37 @@
+#![allow(deprecated)]
+
+use vec_arena::{Arena, DisjointSet};
+
+fn main() {
+    // Connected components of a graph whose nodes live in their own arena.
+    let mut nodes = Arena::new();
+    let names = ["a", "b", "c", "d", "e", "f"];
+    let ids: Vec<usize> = names.iter().map(|name| nodes.insert(*name)).collect();
+
+    // Give every node a set with the same index, so arena indices can be shared.
+    let mut sets = DisjointSet::for_arena(&nodes);
+
+    let edges = [(0, 1), (1, 2), (3, 4)];
+    for &(a, b) in &edges {
+        sets.union(ids[a], ids[b]);
+    }
+
+    assert!(sets.same_set(ids[0], ids[2]));
+    assert!(sets.same_set(ids[3], ids[4]));
+    assert!(!sets.same_set(ids[0], ids[3]));
+    assert!(sets.find(ids[5]) == ids[5]);
+
+    // The components are now {a, b, c}, {d, e} and {f}.
+
+    sets.union(ids[2], ids[5]);
+    assert!(sets.same_set(ids[5], ids[1]));
+
+    // Nodes added later get a set at the same index too.
+    let g = nodes.insert("g");
+    assert!(sets.make_set() == g);
+    assert!(sets.len() == nodes.len());
+
+    for &id in &ids {
+        println!("{} -> {}", nodes[id], nodes[sets.find(id)]);
+    }
+}
@@ -0,0 +1,42 @@
+//! `defmt` logging support, enabled by the `defmt` feature.
+
+use defmt::{Format, Formatter};
+
+use crate::{Arena, FragmentedError, InsertError};
+
+/// Logs a summary of the arena: its length, number of slots, and capacity.
+///
+/// Objects are not logged, so `T` doesn't need to implement [`Format`].
+impl<T> Format for Arena<T> {
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(
+            f,
+            "Arena {{ len: {=usize}, slots: {=usize}, capacity: {=usize} }}",
+            self.len,
+            self.slots.len(),
+            self.slots.capacity()
+        );
+    }
+}
+
+impl<T> Format for FragmentedError<T> {
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(
+            f,
+            "FragmentedError {{ first_vacant: {=usize} }}",
+            self.first_vacant
+        );
+    }
+}
+
+/// Logs the reserve error through its [`Debug`][`core::fmt::Debug`] impl, since
+/// [`TryReserveError`][`alloc::collections::TryReserveError`] doesn't implement [`Format`].
+impl<T> Format for InsertError<T> {
+    fn format(&self, f: Formatter<'_>) {
+        defmt::write!(
+            f,
+            "InsertError {{ error: {} }}",
+            defmt::Debug2Format(&self.error)
+        );
+    }
+}
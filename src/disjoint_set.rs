@@ -0,0 +1,299 @@
+//! A disjoint-set forest whose elements are arena indices.
+
+use alloc::fmt;
+use alloc::vec::Vec;
+
+use crate::{Arena, Slot};
+
+/// An element of the forest.
+struct Set {
+    /// Parent in the tree of the set; a root is its own parent.
+    parent: usize,
+
+    /// Upper bound on the height of the tree rooted here.
+    rank: u32,
+}
+
+/// A disjoint-set forest, also known as union-find.
+///
+/// Every element is an index, and belongs to exactly one set. Each set is identified by its
+/// representative, which is one of its elements. [`find()`][`DisjointSet::find()`] uses path
+/// compression and [`union()`][`DisjointSet::union()`] uses union by rank, so both take nearly
+/// constant amortized time.
+///
+/// Elements are stored in an [`Arena`], and [`for_arena()`][`DisjointSet::for_arena()`] creates
+/// one element for every occupied slot of another arena at the same index, so the two can share
+/// indices.
+///
+/// # Examples
+///
+/// ```
+/// use vec_arena::{Arena, DisjointSet};
+///
+/// let mut nodes = Arena::new();
+/// let a = nodes.insert("a");
+/// let b = nodes.insert("b");
+/// let c = nodes.insert("c");
+///
+/// let mut sets = DisjointSet::for_arena(&nodes);
+/// sets.union(a, b);
+///
+/// assert!(sets.same_set(a, b));
+/// assert!(!sets.same_set(a, c));
+/// ```
+pub struct DisjointSet {
+    /// This is where elements are stored.
+    sets: Arena<Set>,
+}
+
+impl DisjointSet {
+    /// Constructs a new, empty disjoint-set forest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::DisjointSet;
+    ///
+    /// let sets = DisjointSet::new();
+    /// assert!(sets.is_empty());
+    /// ```
+    pub const fn new() -> Self {
+        DisjointSet { sets: Arena::new() }
+    }
+
+    /// Constructs a forest with a singleton set for every occupied slot in `arena`, at the same
+    /// index.
+    ///
+    /// Indices of vacant slots in `arena` are not elements. The free list is copied as well, so
+    /// [`make_set()`][`DisjointSet::make_set()`] hands out the same index as the next
+    /// [`Arena::insert()`] into `arena`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::{Arena, DisjointSet};
+    ///
+    /// let mut nodes = Arena::new();
+    /// let a = nodes.insert("a");
+    /// let b = nodes.insert("b");
+    /// nodes.remove(a);
+    ///
+    /// let mut sets = DisjointSet::for_arena(&nodes);
+    /// assert_eq!(sets.len(), 1);
+    /// assert_eq!(sets.find(b), b);
+    /// assert_eq!(sets.make_set(), nodes.insert("c"));
+    /// ```
+    pub fn for_arena<T>(arena: &Arena<T>) -> Self {
+        let slots: Vec<Slot<Set>> = arena
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(index, slot)| match slot {
+                Slot::Occupied(_) => Slot::Occupied(Set {
+                    parent: index,
+                    rank: 0,
+                }),
+                Slot::Vacant(next) => Slot::Vacant(*next),
+            })
+            .collect();
+        DisjointSet {
+            sets: Arena {
+                slots,
+                len: arena.len,
+                head: arena.head,
+                holes: arena.holes,
+            },
+        }
+    }
+
+    /// Returns the number of elements in the forest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::DisjointSet;
+    ///
+    /// let mut sets = DisjointSet::new();
+    /// sets.make_set();
+    /// assert_eq!(sets.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// Returns `true` if the forest contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::DisjointSet;
+    ///
+    /// let mut sets = DisjointSet::new();
+    /// assert!(sets.is_empty());
+    ///
+    /// sets.make_set();
+    /// assert!(!sets.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Creates a new singleton set and returns its element.
+    ///
+    /// Elements are allocated like [`Arena::insert()`] allocates indices, so a forest built
+    /// alongside an arena by calling this once per insert hands out the same indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::DisjointSet;
+    ///
+    /// let mut sets = DisjointSet::new();
+    /// let a = sets.make_set();
+    /// let b = sets.make_set();
+    ///
+    /// assert_eq!((a, b), (0, 1));
+    /// assert!(!sets.same_set(a, b));
+    /// ```
+    pub fn make_set(&mut self) -> usize {
+        self.sets.insert_with(|index| Set {
+            parent: index,
+            rank: 0,
+        })
+    }
+
+    /// Returns `true` if `x` is an element of the forest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::DisjointSet;
+    ///
+    /// let mut sets = DisjointSet::new();
+    /// let a = sets.make_set();
+    ///
+    /// assert!(sets.contains(a));
+    /// assert!(!sets.contains(a + 1));
+    /// ```
+    #[inline]
+    pub fn contains(&self, x: usize) -> bool {
+        self.sets.get(x).is_some()
+    }
+
+    /// Returns the representative of the set containing `x`.
+    ///
+    /// Every element on the path to the representative is pointed directly at it, which speeds
+    /// up later lookups.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is not an element of the forest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::DisjointSet;
+    ///
+    /// let mut sets = DisjointSet::new();
+    /// let a = sets.make_set();
+    /// let b = sets.make_set();
+    ///
+    /// assert_eq!(sets.find(a), a);
+    /// let root = sets.union(a, b);
+    /// assert_eq!(sets.find(a), root);
+    /// assert_eq!(sets.find(b), root);
+    /// ```
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.sets[root].parent != root {
+            root = self.sets[root].parent;
+        }
+
+        // Point every element on the path directly at the root.
+        let mut node = x;
+        while node != root {
+            let parent = self.sets[node].parent;
+            self.sets[node].parent = root;
+            node = parent;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b` and returns the representative of the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is not an element of the forest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::DisjointSet;
+    ///
+    /// let mut sets = DisjointSet::new();
+    /// let a = sets.make_set();
+    /// let b = sets.make_set();
+    /// let c = sets.make_set();
+    ///
+    /// sets.union(a, b);
+    /// sets.union(b, c);
+    /// assert!(sets.same_set(a, c));
+    /// ```
+    pub fn union(&mut self, a: usize, b: usize) -> usize {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a == b {
+            return a;
+        }
+
+        // Attach the shorter tree below the taller one.
+        let (child, root) = if self.sets[a].rank < self.sets[b].rank {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.sets[child].parent = root;
+        if self.sets[child].rank == self.sets[root].rank {
+            self.sets[root].rank += 1;
+        }
+        root
+    }
+
+    /// Returns `true` if `a` and `b` are in the same set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is not an element of the forest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::DisjointSet;
+    ///
+    /// let mut sets = DisjointSet::new();
+    /// let a = sets.make_set();
+    /// let b = sets.make_set();
+    ///
+    /// assert!(!sets.same_set(a, b));
+    /// sets.union(a, b);
+    /// assert!(sets.same_set(a, b));
+    /// ```
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+impl Default for DisjointSet {
+    fn default() -> Self {
+        DisjointSet::new()
+    }
+}
+
+impl fmt::Debug for DisjointSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DisjointSet {{ ... }}")
+    }
+}
@@ -12,6 +12,18 @@
 //!
 //! * [Doubly linked list](https://github.com/smol-rs/vec-arena/blob/master/examples/linked-list.rs)
 //! * [Splay tree](https://github.com/smol-rs/vec-arena/blob/master/examples/splay-tree.rs)
+//! * [LRU cache](https://github.com/smol-rs/vec-arena/blob/master/examples/lru-cache.rs)
+//! * [Priority queue](https://github.com/smol-rs/vec-arena/blob/master/examples/priority-queue.rs),
+//!   using the [`PriorityQueue`] that ships with this crate
+//! * [Union-find](https://github.com/smol-rs/vec-arena/blob/master/examples/union-find.rs),
+//!   using the [`DisjointSet`] that ships with this crate
+//!
+//! # Features
+//!
+//! * `defmt`: implements `defmt::Format` for arenas and error types, logging a summary of the
+//!   arena without its objects.
+//! * `rayon`: implements `ParallelExtend` so arenas can be filled from parallel iterators, and
+//!   adds `Arena::par_retain()` and `Arena::par_drop()`.
 
 #![no_std]
 #![forbid(unsafe_code)]
@@ -23,14 +35,31 @@
 
 extern crate alloc;
 
+use alloc::collections::TryReserveError;
 use alloc::fmt;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
 use core::iter;
 use core::mem;
-use core::ops::{Index, IndexMut};
+use core::ops::{Deref, DerefMut, Index, IndexMut, Range};
 use core::slice;
 
+#[cfg(feature = "defmt")]
+mod defmt_impls;
+mod disjoint_set;
+mod priority_queue;
+#[cfg(feature = "rayon")]
+mod rayon_impls;
+
+pub use crate::disjoint_set::DisjointSet;
+pub use crate::priority_queue::PriorityQueue;
+
+/// Number of bits in a word of [`Arena::occupancy_words()`].
+const WORD_BITS: usize = mem::size_of::<usize>() * 8;
+
 /// A slot, which is either vacant or occupied.
 ///
 /// Vacant slots in arena are linked together into a singly linked list. This allows the arena to
@@ -108,6 +137,9 @@ impl<T> Slot<T> {
 ///
 /// To access slots without fear of panicking, use [`get()`][`Arena::get()`] and
 /// [`get_mut()`][`Arena::get_mut()`], which return [`Option`]s.
+///
+/// Index `!0` (that is, [`usize::MAX`]) is never a valid index, so it can safely be used as a
+/// null index in data structures built on top of an arena.
 pub struct Arena<T> {
     /// Slots in which objects are stored.
     slots: Vec<Slot<T>>,
@@ -115,8 +147,15 @@ pub struct Arena<T> {
     /// Number of occupied slots in the arena.
     len: usize,
 
-    /// Index of the first vacant slot in the linked list.
+    /// Index of the first vacant slot in the linked list, or `!0` if there are none.
+    ///
+    /// `!0` can never collide with a real slot index: every slot is at least as large as a
+    /// `usize`, and a `Vec` never holds more than `isize::MAX` bytes, so there are always fewer
+    /// than `usize::MAX / 2` slots.
     head: usize,
+
+    /// Number of vacant slots below `len`, which is zero exactly when the arena is dense.
+    holes: usize,
 }
 
 impl<T> Arena<T> {
@@ -124,19 +163,27 @@ impl<T> Arena<T> {
     ///
     /// The arena will not allocate until objects are inserted into it.
     ///
+    /// This is a `const fn`, so an arena can be placed directly in a `static`.
+    ///
     /// # Examples
     ///
     /// ```
+    /// use std::sync::Mutex;
     /// use vec_arena::Arena;
     ///
     /// let mut arena: Arena<i32> = Arena::new();
+    ///
+    /// static REGISTRY: Mutex<Arena<&str>> = Mutex::new(Arena::new());
+    /// let index = REGISTRY.lock().unwrap().insert("hello");
+    /// assert_eq!(REGISTRY.lock().unwrap()[index], "hello");
     /// ```
     #[inline]
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Arena {
             slots: Vec::new(),
             len: 0,
             head: !0,
+            holes: 0,
         }
     }
 
@@ -171,6 +218,7 @@ impl<T> Arena<T> {
             slots: Vec::with_capacity(cap),
             len: 0,
             head: !0,
+            holes: 0,
         }
     }
 
@@ -227,6 +275,33 @@ impl<T> Arena<T> {
         self.len == 0
     }
 
+    /// Returns `true` if the occupied slots are exactly `0..len()`, with no vacant slots in
+    /// between.
+    ///
+    /// This takes constant time: the arena keeps count of the vacant slots below `len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    /// assert!(arena.is_dense());
+    ///
+    /// arena.remove(b);
+    /// assert!(arena.is_dense());
+    ///
+    /// arena.insert(3);
+    /// arena.remove(a);
+    /// assert!(!arena.is_dense());
+    /// ```
+    #[inline]
+    pub fn is_dense(&self) -> bool {
+        self.holes == 0
+    }
+
     /// Returns the index of the slot that next [`insert`][`Arena::insert()`] will use if no
     /// mutating calls take place in between.
     ///
@@ -284,13 +359,13 @@ impl<T> Arena<T> {
                 }
                 Slot::Occupied(_) => unreachable!(),
             }
+            self.count_occupied(index);
             index
         }
     }
 
-    /// Removes the object stored at `index` from the arena and returns it.
-    ///
-    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    /// Inserts an object into the arena and returns its index along with a mutable reference to
+    /// it.
     ///
     /// # Examples
     ///
@@ -298,34 +373,25 @@ impl<T> Arena<T> {
     /// use vec_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// let a = arena.insert("hello");
-    ///
-    /// assert_eq!(arena.len(), 1);
-    /// assert_eq!(arena.remove(a), Some("hello"));
     ///
-    /// assert_eq!(arena.len(), 0);
-    /// assert_eq!(arena.remove(a), None);
+    /// let (index, object) = arena.insert_and_get_mut(vec![1]);
+    /// object.push(2);
+    /// assert_eq!(arena[index], [1, 2]);
     /// ```
     #[inline]
-    pub fn remove(&mut self, index: usize) -> Option<T> {
-        match self.slots.get_mut(index) {
-            None => None,
-            Some(&mut Slot::Vacant(_)) => None,
-            Some(slot @ &mut Slot::Occupied(_)) => {
-                if let Slot::Occupied(object) = mem::replace(slot, Slot::Vacant(self.head)) {
-                    self.head = index;
-                    self.len -= 1;
-                    Some(object)
-                } else {
-                    unreachable!();
-                }
-            }
+    pub fn insert_and_get_mut(&mut self, object: T) -> (usize, &mut T) {
+        let index = self.insert(object);
+        match &mut self.slots[index] {
+            Slot::Vacant(_) => unreachable!(),
+            Slot::Occupied(object) => (index, object),
         }
     }
 
-    /// Retains objects for which the closure returns `true`.
+    /// Returns a handle to the vacant slot the next object will be inserted into.
     ///
-    /// All other objects will be removed from the arena.
+    /// The handle borrows the arena mutably, so the index it reports stays valid until
+    /// [`VacantEntry::insert()`] fills the slot. This is useful for objects that need to contain
+    /// their own index.
     ///
     /// # Examples
     ///
@@ -333,33 +399,28 @@ impl<T> Arena<T> {
     /// use vec_arena::Arena;
     ///
     /// let mut arena = Arena::new();
+    /// arena.insert((0, "a"));
     ///
-    /// let a = arena.insert(0);
-    /// let b = arena.insert(1);
-    /// let c = arena.insert(2);
-    ///
-    /// arena.retain(|k, v| k == a || *v == 1);
+    /// let entry = arena.vacant_entry();
+    /// let index = entry.index();
+    /// entry.insert((index, "b"));
     ///
-    /// assert!(arena.get(a).is_some());
-    /// assert!(arena.get(b).is_some());
-    /// assert!(arena.get(c).is_none());
+    /// assert_eq!(arena[index], (1, "b"));
     /// ```
-    pub fn retain<F>(&mut self, mut f: F)
-    where
-        F: FnMut(usize, &mut T) -> bool,
-    {
-        for i in 0..self.slots.len() {
-            if let Slot::Occupied(v) = &mut self.slots[i] {
-                if !f(i, v) {
-                    self.remove(i);
-                }
-            }
+    #[inline]
+    pub fn vacant_entry(&mut self) -> VacantEntry<'_, T> {
+        VacantEntry {
+            index: self.next_vacant(),
+            arena: self,
         }
     }
 
-    /// Clears the arena, removing and dropping all objects it holds.
+    /// Inserts an object tentatively, returning a guard that removes it again unless committed.
     ///
-    /// Keeps the allocated memory for reuse.
+    /// The guard dereferences to the inserted object and reports its index through
+    /// [`TentativeInsert::index()`]. Calling [`TentativeInsert::commit()`] keeps the object in the
+    /// arena; dropping the guard without committing removes it, for example when a multi-step
+    /// registration bails out early with `?`.
     ///
     /// # Examples
     ///
@@ -367,24 +428,31 @@ impl<T> Arena<T> {
     /// use vec_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// for i in 0..10 {
-    ///     arena.insert(i);
-    /// }
     ///
-    /// assert_eq!(arena.len(), 10);
-    /// arena.clear();
-    /// assert_eq!(arena.len(), 0);
+    /// let entry = arena.insert_tentative("a");
+    /// assert_eq!(entry.index(), 0);
+    /// drop(entry);
+    /// assert!(arena.is_empty());
+    ///
+    /// let mut entry = arena.insert_tentative("b");
+    /// *entry = "c";
+    /// let index = entry.commit();
+    /// assert_eq!(arena[index], "c");
     /// ```
-    #[inline]
-    pub fn clear(&mut self) {
-        self.slots.clear();
-        self.len = 0;
-        self.head = !0;
+    pub fn insert_tentative(&mut self, object: T) -> TentativeInsert<'_, T> {
+        let index = self.insert(object);
+        TentativeInsert {
+            arena: self,
+            index,
+            committed: false,
+        }
     }
 
-    /// Returns a reference to the object stored at `index`.
+    /// Inserts an object into the arena without aborting on allocation failure.
     ///
-    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    /// This is like [`insert()`][`Arena::insert()`], except that if the arena is full and
+    /// growing it fails, the object is handed back inside an error instead of the process
+    /// aborting.
     ///
     /// # Examples
     ///
@@ -392,86 +460,78 @@ impl<T> Arena<T> {
     /// use vec_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// let index = arena.insert("hello");
     ///
-    /// assert_eq!(arena.get(index), Some(&"hello"));
-    /// arena.remove(index);
-    /// assert_eq!(arena.get(index), None);
+    /// match arena.insert_or_err("hello") {
+    ///     Ok(index) => assert_eq!(arena[index], "hello"),
+    ///     Err(err) => println!("out of memory, dropping {}", err.into_inner()),
+    /// }
     /// ```
-    #[inline]
-    pub fn get(&self, index: usize) -> Option<&T> {
-        match self.slots.get(index) {
-            None => None,
-            Some(&Slot::Vacant(_)) => None,
-            Some(&Slot::Occupied(ref object)) => Some(object),
+    pub fn insert_or_err(&mut self, object: T) -> Result<usize, InsertError<T>> {
+        match self.try_reserve(1) {
+            Ok(()) => Ok(self.insert(object)),
+            Err(error) => Err(InsertError { object, error }),
         }
     }
 
-    /// Returns a mutable reference to the object stored at `index`.
+    /// Inserts an object into the arena only if that can be done without allocating.
     ///
-    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    /// The object is stored in a vacant slot or in spare capacity of the slot array. If neither
+    /// is available, the object is handed back instead, so this never allocates or reallocates.
+    /// Combined with [`reserve()`][`Arena::reserve()`] up front, this is suitable for code that
+    /// must not touch the allocator, such as real-time threads.
     ///
     /// # Examples
     ///
     /// ```
     /// use vec_arena::Arena;
     ///
-    /// let mut arena = Arena::new();
-    /// let index = arena.insert(7);
+    /// let mut arena = Arena::with_capacity(2);
     ///
-    /// assert_eq!(arena.get_mut(index), Some(&mut 7));
-    /// *arena.get_mut(index).unwrap() *= 10;
-    /// assert_eq!(arena.get_mut(index), Some(&mut 70));
+    /// assert_eq!(arena.insert_within_capacity("a"), Ok(0));
+    /// assert_eq!(arena.insert_within_capacity("b"), Ok(1));
+    /// assert_eq!(arena.insert_within_capacity("c"), Err("c"));
+    ///
+    /// arena.remove(0);
+    /// assert_eq!(arena.insert_within_capacity("c"), Ok(0));
     /// ```
-    #[inline]
-    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
-        match self.slots.get_mut(index) {
-            None => None,
-            Some(&mut Slot::Vacant(_)) => None,
-            Some(&mut Slot::Occupied(ref mut object)) => Some(object),
+    pub fn insert_within_capacity(&mut self, object: T) -> Result<usize, T> {
+        if self.head == !0 && self.slots.len() == self.slots.capacity() {
+            Err(object)
+        } else {
+            Ok(self.insert(object))
         }
     }
 
-    /// Swaps two objects in the arena.
-    ///
-    /// The two indices are `a` and `b`.
-    ///
-    /// # Panics
+    /// Returns a handle to the arena that only exposes operations which never allocate.
     ///
-    /// Panics if any of the indices is out of bounds or any of the slots is vacant.
+    /// The handle's [`insert()`][`FixedArena::insert()`] hands the object back when the arena is
+    /// full instead of growing it, and no method on the handle reallocates or frees the slot array.
+    /// Passing a [`FixedArena`] to code that must not touch the allocator, such as a real-time
+    /// thread, makes that guarantee part of its signature.
     ///
     /// # Examples
     ///
     /// ```
     /// use vec_arena::Arena;
     ///
-    /// let mut arena = Arena::new();
-    /// let a = arena.insert(7);
-    /// let b = arena.insert(8);
+    /// let mut arena = Arena::with_capacity(2);
+    /// let mut fixed = arena.fixed();
     ///
-    /// arena.swap(a, b);
-    /// assert_eq!(arena.get(a), Some(&8));
-    /// assert_eq!(arena.get(b), Some(&7));
+    /// assert_eq!(fixed.insert("a"), Ok(0));
+    /// assert_eq!(fixed.insert("b"), Ok(1));
+    /// assert_eq!(fixed.insert("c"), Err("c"));
+    ///
+    /// assert_eq!(fixed.remove(0), Some("a"));
+    /// assert_eq!(fixed.insert("c"), Ok(0));
     /// ```
     #[inline]
-    pub fn swap(&mut self, a: usize, b: usize) {
-        assert!(self.slots[a].is_occupied(), "invalid object ID");
-        assert!(self.slots[b].is_occupied(), "invalid object ID");
-
-        if a != b {
-            let (a, b) = (a.min(b), a.max(b));
-            let (l, r) = self.slots.split_at_mut(b);
-            mem::swap(&mut l[a], &mut r[0]);
-        }
+    pub fn fixed(&mut self) -> FixedArena<'_, T> {
+        FixedArena { arena: self }
     }
 
-    /// Reserves capacity for at least `additional` more objects to be inserted.
-    ///
-    /// The arena may reserve more space to avoid frequent reallocations.
-    ///
-    /// # Panics
+    /// Inserts an object built by a closure that receives the index it will be stored at.
     ///
-    /// Panics if the new capacity overflows `usize`.
+    /// Returns the index where the object is stored.
     ///
     /// # Examples
     ///
@@ -479,25 +539,26 @@ impl<T> Arena<T> {
     /// use vec_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// arena.insert("hello");
     ///
-    /// arena.reserve(10);
-    /// assert!(arena.capacity() >= 11);
+    /// let a = arena.insert_with(|index| (index, "a"));
+    /// let b = arena.insert_with(|index| (index, "b"));
+    ///
+    /// assert_eq!(arena[a], (0, "a"));
+    /// assert_eq!(arena[b], (1, "b"));
     /// ```
-    pub fn reserve(&mut self, additional: usize) {
-        let vacant = self.slots.len() - self.len;
-        if additional > vacant {
-            self.slots.reserve(additional - vacant);
-        }
+    #[inline]
+    pub fn insert_with<F>(&mut self, f: F) -> usize
+    where
+        F: FnOnce(usize) -> T,
+    {
+        let index = self.next_vacant();
+        self.insert(f(index))
     }
 
-    /// Reserves the minimum capacity for exactly `additional` more objects to be inserted.
-    ///
-    /// Note that the allocator may give the arena more space than it requests.
-    ///
-    /// # Panics
+    /// Inserts an object built by a fallible closure that receives the index it will be stored
+    /// at.
     ///
-    /// Panics if the new capacity overflows `usize`.
+    /// If the closure returns an error, the arena is left unchanged and the error is returned.
     ///
     /// # Examples
     ///
@@ -505,19 +566,27 @@ impl<T> Arena<T> {
     /// use vec_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// arena.insert("hello");
     ///
-    /// arena.reserve_exact(10);
-    /// assert!(arena.capacity() >= 11);
+    /// let a = arena.try_insert_with(|index| Ok::<_, ()>((index, "a")));
+    /// assert_eq!(a, Ok(0));
+    /// assert_eq!(arena[0], (0, "a"));
+    ///
+    /// let b = arena.try_insert_with(|_| Err("failed"));
+    /// assert_eq!(b, Err("failed"));
+    /// assert_eq!(arena.len(), 1);
     /// ```
-    pub fn reserve_exact(&mut self, additional: usize) {
-        let vacant = self.slots.len() - self.len;
-        if additional > vacant {
-            self.slots.reserve_exact(additional - vacant);
-        }
+    pub fn try_insert_with<F, E>(&mut self, f: F) -> Result<usize, E>
+    where
+        F: FnOnce(usize) -> Result<T, E>,
+    {
+        let index = self.next_vacant();
+        let object = f(index)?;
+        Ok(self.insert(object))
     }
 
-    /// Returns an iterator over occupied slots.
+    /// Removes the object stored at `index` from the arena and returns it.
+    ///
+    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
     ///
     /// # Examples
     ///
@@ -525,108 +594,2080 @@ impl<T> Arena<T> {
     /// use vec_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// arena.insert(1);
-    /// arena.insert(2);
-    /// arena.insert(4);
+    /// let a = arena.insert("hello");
     ///
-    /// let mut iterator = arena.iter();
-    /// assert_eq!(iterator.next(), Some((0, &1)));
-    /// assert_eq!(iterator.next(), Some((1, &2)));
-    /// assert_eq!(iterator.next(), Some((2, &4)));
+    /// assert_eq!(arena.len(), 1);
+    /// assert_eq!(arena.remove(a), Some("hello"));
+    ///
+    /// assert_eq!(arena.len(), 0);
+    /// assert_eq!(arena.remove(a), None);
     /// ```
     #[inline]
-    pub fn iter(&self) -> Iter<'_, T> {
-        Iter {
-            slots: self.slots.iter().enumerate(),
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        match self.slots.get_mut(index) {
+            None => None,
+            Some(&mut Slot::Vacant(_)) => None,
+            Some(slot @ &mut Slot::Occupied(_)) => {
+                if let Slot::Occupied(object) = mem::replace(slot, Slot::Vacant(self.head)) {
+                    self.head = index;
+                    self.len -= 1;
+                    self.count_vacated(index);
+                    Some(object)
+                } else {
+                    unreachable!();
+                }
+            }
         }
     }
 
-    /// Returns an iterator that returns mutable references to objects.
+    /// Moves the object stored at `index` into `other` and returns its index there.
+    ///
+    /// The slot at `index` becomes vacant in this arena. If the slot is vacant or `index` is out
+    /// of bounds, [`None`] will be returned and neither arena is changed. Room is made in `other`
+    /// before the object leaves this arena, so a failed allocation cannot lose it.
     ///
     /// # Examples
     ///
     /// ```
     /// use vec_arena::Arena;
     ///
-    /// let mut arena = Arena::new();
-    /// arena.insert("zero".to_string());
-    /// arena.insert("one".to_string());
-    /// arena.insert("two".to_string());
+    /// let mut level1 = Arena::new();
+    /// let mut level2 = Arena::new();
+    /// level2.insert("tree");
     ///
-    /// for (index, object) in arena.iter_mut() {
-    ///     *object = index.to_string() + " " + object;
-    /// }
+    /// let player = level1.insert("player");
     ///
-    /// let mut iterator = arena.iter();
-    /// assert_eq!(iterator.next(), Some((0, &"0 zero".to_string())));
-    /// assert_eq!(iterator.next(), Some((1, &"1 one".to_string())));
-    /// assert_eq!(iterator.next(), Some((2, &"2 two".to_string())));
+    /// assert_eq!(level1.transfer(player, &mut level2), Some(1));
+    /// assert_eq!(level1.get(player), None);
+    /// assert_eq!(level2[1], "player");
+    ///
+    /// assert_eq!(level1.transfer(player, &mut level2), None);
     /// ```
-    #[inline]
-    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
-        IterMut {
-            slots: self.slots.iter_mut().enumerate(),
+    pub fn transfer(&mut self, index: usize, other: &mut Arena<T>) -> Option<usize> {
+        match self.slots.get(index) {
+            Some(slot) if slot.is_occupied() => {}
+            _ => return None,
+        }
+
+        // Make room in `other` first, so that neither arena is changed if growing it panics.
+        other.reserve(1);
+
+        let slot = mem::replace(&mut self.slots[index], Slot::Vacant(self.head));
+        self.head = index;
+        self.len -= 1;
+        self.count_vacated(index);
+
+        match slot {
+            Slot::Occupied(object) => Some(other.insert(object)),
+            Slot::Vacant(_) => unreachable!(),
         }
     }
 
-    /// Shrinks the capacity of the arena as much as possible.
+    /// Removes the object stored at `index` and fills the hole with the object at the highest
+    /// occupied index.
     ///
-    /// It will drop down as close as possible to the length but the allocator may still inform
-    /// the arena that there is space for a few more elements.
+    /// Returns the removed object and, if an object was relocated into `index`, the index it was
+    /// moved from. References to that index must be updated by the caller.
+    ///
+    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    ///
+    /// Unlike [`remove()`][`Arena::remove()`], this keeps a dense arena dense, at the cost of
+    /// changing the index of one other object.
     ///
     /// # Examples
     ///
     /// ```
     /// use vec_arena::Arena;
     ///
-    /// let mut arena = Arena::with_capacity(10);
-    /// arena.insert("first".to_string());
-    /// arena.insert("second".to_string());
-    /// arena.insert("third".to_string());
-    /// assert_eq!(arena.capacity(), 10);
-    /// arena.shrink_to_fit();
-    /// assert!(arena.capacity() >= 3);
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// let b = arena.insert("b");
+    /// let c = arena.insert("c");
+    ///
+    /// assert_eq!(arena.swap_remove(a), Some(("a", Some(c))));
+    /// assert_eq!(arena.get(a), Some(&"c"));
+    /// assert_eq!(arena.get(c), None);
+    ///
+    /// assert_eq!(arena.swap_remove(b), Some(("b", None)));
+    /// assert_eq!(arena.insert("d"), 1);
     /// ```
-    pub fn shrink_to_fit(&mut self) {
-        self.slots.shrink_to_fit();
-    }
-}
+    pub fn swap_remove(&mut self, index: usize) -> Option<(T, Option<usize>)> {
+        let object = self.remove(index)?;
+        let mut moved = None;
 
-impl<T> fmt::Debug for Arena<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Arena {{ ... }}")
-    }
-}
+        if let Some(last) = self.slots.iter().rposition(Slot::is_occupied) {
+            if last > index {
+                // The removed slot is now the head of the vacant list. Swapping moves that vacant
+                // entry to `last`, so `last` becomes the new head.
+                self.slots.swap(index, last);
+                self.head = last;
+                moved = Some(last);
 
-impl<T> Index<usize> for Arena<T> {
-    type Output = T;
+                // `last` is the highest occupied index, so it is at or above `len` and doesn't
+                // count as a hole once vacated.
+                if index < self.len {
+                    self.holes -= 1;
+                }
+            }
+        }
 
-    #[inline]
-    fn index(&self, index: usize) -> &T {
+        // Drop vacant slots from the end of the array while they're at the head of the list.
+        while self.head != !0 && self.head == self.slots.len() - 1 {
+            match self.slots.pop() {
+                Some(Slot::Vacant(next)) => self.head = next,
+                _ => unreachable!(),
+            }
+        }
+
+        Some((object, moved))
+    }
+
+    /// Retains objects for which the closure returns `true`.
+    ///
+    /// All other objects will be removed from the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    ///
+    /// let a = arena.insert(0);
+    /// let b = arena.insert(1);
+    /// let c = arena.insert(2);
+    ///
+    /// arena.retain(|k, v| k == a || *v == 1);
+    ///
+    /// assert!(arena.get(a).is_some());
+    /// assert!(arena.get(b).is_some());
+    /// assert!(arena.get(c).is_none());
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        for i in 0..self.slots.len() {
+            if let Slot::Occupied(v) = &mut self.slots[i] {
+                if !f(i, v) {
+                    self.remove(i);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator that removes and yields the objects for which the closure returns
+    /// `true`, along with their indices.
+    ///
+    /// Slots are visited in index order, and only as far as the iterator is consumed; objects
+    /// past that point are left in place. Objects that are kept keep their indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..6).collect();
+    ///
+    /// let odd: Vec<_> = arena.extract_if(|_, v| *v % 2 == 1).collect();
+    /// assert_eq!(odd, [(1, 1), (3, 3), (5, 5)]);
+    /// assert_eq!(arena.len(), 3);
+    /// assert_eq!(arena[4], 4);
+    /// ```
+    #[inline]
+    pub fn extract_if<F>(&mut self, f: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(usize, &mut T) -> bool,
+    {
+        ExtractIf {
+            arena: self,
+            index: 0,
+            f,
+        }
+    }
+
+    /// Consumes the arena and maps its objects into a new arena, keeping their indices.
+    ///
+    /// Objects for which the closure returns [`None`] are dropped and their slots become vacant,
+    /// so every index in the new arena refers to the same entry as in the old one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("1");
+    /// let b = arena.insert("x");
+    /// let c = arena.insert("3");
+    ///
+    /// let numbers: Arena<i32> = arena.filter_map(|_, s| s.parse().ok());
+    ///
+    /// assert_eq!(numbers.get(a), Some(&1));
+    /// assert_eq!(numbers.get(b), None);
+    /// assert_eq!(numbers.get(c), Some(&3));
+    /// ```
+    pub fn filter_map<U, F>(self, mut f: F) -> Arena<U>
+    where
+        F: FnMut(usize, T) -> Option<U>,
+    {
+        let slots = self
+            .slots
+            .into_iter()
+            .enumerate()
+            .map(|(index, slot)| match slot {
+                Slot::Occupied(object) => match f(index, object) {
+                    Some(object) => Slot::Occupied(object),
+                    None => Slot::Vacant(!0),
+                },
+                Slot::Vacant(_) => Slot::Vacant(!0),
+            })
+            .collect();
+        Arena::from_slots(slots)
+    }
+
+    /// Consumes the arena and splits it into two arenas over the same indices.
+    ///
+    /// Objects for which the predicate returns `true` go into the first arena, and the rest go
+    /// into the second. Every object keeps its index, and each slot occupied in the original arena
+    /// is occupied in exactly one of the two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    /// let c = arena.insert(3);
+    ///
+    /// let (odd, even) = arena.partition(|_, v| v % 2 == 1);
+    ///
+    /// assert_eq!(odd.get(a), Some(&1));
+    /// assert_eq!(odd.get(b), None);
+    /// assert_eq!(odd.get(c), Some(&3));
+    /// assert_eq!(even.get(b), Some(&2));
+    /// assert_eq!(even.len(), 1);
+    /// ```
+    pub fn partition<F>(self, mut f: F) -> (Arena<T>, Arena<T>)
+    where
+        F: FnMut(usize, &T) -> bool,
+    {
+        let mut left = Vec::with_capacity(self.slots.len());
+        let mut right = Vec::with_capacity(self.slots.len());
+
+        for (index, slot) in self.slots.into_iter().enumerate() {
+            match slot {
+                Slot::Occupied(object) => {
+                    if f(index, &object) {
+                        left.push(Slot::Occupied(object));
+                        right.push(Slot::Vacant(!0));
+                    } else {
+                        left.push(Slot::Vacant(!0));
+                        right.push(Slot::Occupied(object));
+                    }
+                }
+                Slot::Vacant(_) => {
+                    left.push(Slot::Vacant(!0));
+                    right.push(Slot::Vacant(!0));
+                }
+            }
+        }
+
+        (Arena::from_slots(left), Arena::from_slots(right))
+    }
+
+    /// Splits the arena in two at index `at`, returning the objects stored at `at` and above in a
+    /// new arena.
+    ///
+    /// Objects keep their indices in both arenas: slots below `at` are vacant in the returned
+    /// arena, and this arena is left with only the slots below `at`. Vacant slots in both arenas
+    /// are relinked so that lower indices are reused first. If `at` is past the last slot, the
+    /// returned arena is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..5).collect();
+    ///
+    /// let tail = arena.split_off(3);
+    /// assert_eq!(arena.len(), 3);
+    /// assert_eq!(tail.len(), 2);
+    /// assert_eq!(tail.get(2), None);
+    /// assert_eq!(tail[3], 3);
+    /// assert_eq!(tail[4], 4);
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Arena<T> {
+        if at >= self.slots.len() {
+            return Arena::new();
+        }
+
+        let mut slots = Vec::with_capacity(self.slots.len());
+        slots.resize_with(at, || Slot::Vacant(!0));
+        slots.extend(self.slots.drain(at..));
+
+        self.relink();
+        Arena::from_slots(slots)
+    }
+
+    /// Inserts all objects from an iterator and returns the index each one was placed at.
+    ///
+    /// Objects are inserted in iteration order, so the returned indices are the same as a series
+    /// of [`insert()`][`Arena::insert()`] calls would give.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// arena.insert("b");
+    /// arena.remove(a);
+    ///
+    /// let indices = arena.extend_returning(vec!["c", "d", "e"]);
+    /// assert_eq!(indices, [0, 2, 3]);
+    /// assert_eq!(arena[2], "d");
+    /// ```
+    pub fn extend_returning<I>(&mut self, objects: I) -> Vec<usize>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let objects = objects.into_iter();
+        self.reserve(objects.size_hint().0);
+        objects.map(|object| self.insert(object)).collect()
+    }
+
+    /// Inserts clones of all objects in `other` and returns where each one was placed.
+    ///
+    /// The returned vector is indexed by slot index in `other`: the element at `i` is the new
+    /// index of the clone of `other[i]`, or [`None`] if that slot in `other` is vacant. Objects
+    /// are inserted in index order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut template = Arena::new();
+    /// let a = template.insert("a");
+    /// let b = template.insert("b");
+    /// template.remove(a);
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert("x");
+    ///
+    /// let map = arena.extend_from_arena(&template);
+    /// assert_eq!(map, [None, Some(1)]);
+    /// assert_eq!(arena[map[b].unwrap()], "b");
+    /// ```
+    pub fn extend_from_arena(&mut self, other: &Arena<T>) -> Vec<Option<usize>>
+    where
+        T: Clone,
+    {
+        self.reserve(other.len);
+
+        let mut map = vec![None; other.slots.len()];
+        for (index, object) in other.iter() {
+            map[index] = Some(self.insert(object.clone()));
+        }
+        map
+    }
+
+    /// Removes objects equal to an object at a lower index and returns how many were removed.
+    ///
+    /// Of every group of equal objects, only the one with the lowest index is kept. Unlike
+    /// [`Vec::dedup()`], which only removes consecutive duplicates, this removes duplicates
+    /// wherever they are in the arena. Objects are compared by sorting, so this takes
+    /// `O(n log n)` time rather than comparing every pair.
+    ///
+    /// This requires `T: Ord` because the crate is `no_std` and has no hash map to find duplicates
+    /// with. For types that are only [`PartialEq`], use
+    /// [`dedup_all_by()`][`Arena::dedup_all_by()`] with `|a, b| a == b` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<_> = ["a", "b", "a", "c", "b"].iter().copied().collect();
+    ///
+    /// assert_eq!(arena.dedup_all(), 2);
+    /// assert_eq!(arena.iter().collect::<Vec<_>>(), [(0, &"a"), (1, &"b"), (3, &"c")]);
+    /// ```
+    pub fn dedup_all(&mut self) -> usize
+    where
+        T: Ord,
+    {
+        let mut indices: Vec<usize> = self.iter().map(|(index, _)| index).collect();
+        indices.sort_by(|&a, &b| self[a].cmp(&self[b]));
+
+        // The sort is stable, so the first index in every group of equal objects is the lowest.
+        let duplicates: Vec<usize> = indices
+            .windows(2)
+            .filter(|pair| self[pair[0]] == self[pair[1]])
+            .map(|pair| pair[1])
+            .collect();
+
+        for &index in &duplicates {
+            self.remove(index);
+        }
+        duplicates.len()
+    }
+
+    /// Removes objects whose key equals the key of an object at a lower index and returns how many
+    /// were removed.
+    ///
+    /// Of every group of objects with equal keys, only the one with the lowest index is kept.
+    /// Unlike [`Vec::dedup_by_key()`], this is not limited to consecutive objects. The key function
+    /// is called once per object, and keys are compared by sorting, so this takes `O(n log n)`
+    /// time rather than comparing every pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("apple");
+    /// let b = arena.insert("avocado");
+    /// let c = arena.insert("banana");
+    ///
+    /// assert_eq!(arena.dedup_all_by_key(|s| s.chars().next()), 1);
+    /// assert!(arena.get(a).is_some());
+    /// assert!(arena.get(b).is_none());
+    /// assert!(arena.get(c).is_some());
+    /// ```
+    pub fn dedup_all_by_key<K, F>(&mut self, mut key: F) -> usize
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        let mut keys: Vec<(K, usize)> = self
+            .iter()
+            .map(|(index, object)| (key(object), index))
+            .collect();
+        keys.sort_unstable();
+
+        let mut removed = 0;
+        for pair in keys.windows(2) {
+            if pair[0].0 == pair[1].0 {
+                self.remove(pair[1].1);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// Removes objects for which `same` returns `true` when compared with an object at a lower
+    /// index, and returns how many were removed.
+    ///
+    /// `same(a, b)` is called with the object at the lower index first. Of every group of objects
+    /// that `same` considers equal, only the one with the lowest index is kept. Unlike
+    /// [`Vec::dedup_by()`], this is not limited to consecutive objects.
+    ///
+    /// Every remaining pair of objects is compared, so this takes `O(n²)` time. When `T` is
+    /// [`Ord`], [`dedup_all()`][`Arena::dedup_all()`] and
+    /// [`dedup_all_by_key()`][`Arena::dedup_all_by_key()`] are faster.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<_> = [1.0, 2.0, 1.0, 2.5, 2.0].iter().copied().collect();
+    ///
+    /// assert_eq!(arena.dedup_all_by(|a, b| a == b), 2);
+    /// assert_eq!(arena.iter().collect::<Vec<_>>(), [(0, &1.0), (1, &2.0), (3, &2.5)]);
+    /// ```
+    pub fn dedup_all_by<F>(&mut self, mut same: F) -> usize
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut removed = 0;
+        for i in 0..self.slots.len() {
+            for j in i + 1..self.slots.len() {
+                let duplicate = match (&self.slots[i], &self.slots[j]) {
+                    (Slot::Occupied(a), Slot::Occupied(b)) => same(a, b),
+                    _ => false,
+                };
+                if duplicate {
+                    self.remove(j);
+                    removed += 1;
+                }
+            }
+        }
+        removed
+    }
+
+    /// Clears the arena, removing and dropping all objects it holds.
+    ///
+    /// Keeps the allocated memory for reuse. If `T` doesn't need to be dropped (see
+    /// [`core::mem::needs_drop()`]), this takes constant time regardless of the number of slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// assert_eq!(arena.len(), 10);
+    /// arena.clear();
+    /// assert_eq!(arena.len(), 0);
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.len = 0;
+        self.head = !0;
+        self.holes = 0;
+    }
+
+    /// Removes all objects from the arena and returns them in an iterator, along with their
+    /// indices.
+    ///
+    /// The arena is empty once this returns, even if the iterator is not fully consumed; objects
+    /// not yielded are dropped along with the iterator. The allocated capacity is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert("a");
+    /// let b = arena.insert("b");
+    /// arena.insert("c");
+    /// arena.remove(b);
+    ///
+    /// let drained: Vec<_> = arena.drain().collect();
+    /// assert_eq!(drained, [(0, "a"), (2, "c")]);
+    /// assert!(arena.is_empty());
+    /// assert!(arena.capacity() >= 3);
+    /// ```
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        self.len = 0;
+        self.head = !0;
+        self.holes = 0;
+        Drain {
+            slots: self.slots.drain(..).enumerate(),
+        }
+    }
+
+    /// Returns a reference to the object stored at `index`.
+    ///
+    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let index = arena.insert("hello");
+    ///
+    /// assert_eq!(arena.get(index), Some(&"hello"));
+    /// arena.remove(index);
+    /// assert_eq!(arena.get(index), None);
+    /// ```
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match self.slots.get(index) {
+            None => None,
+            Some(Slot::Vacant(_)) => None,
+            Some(Slot::Occupied(object)) => Some(object),
+        }
+    }
+
+    /// Returns a mutable reference to the object stored at `index`.
+    ///
+    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let index = arena.insert(7);
+    ///
+    /// assert_eq!(arena.get_mut(index), Some(&mut 7));
+    /// *arena.get_mut(index).unwrap() *= 10;
+    /// assert_eq!(arena.get_mut(index), Some(&mut 70));
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        match self.slots.get_mut(index) {
+            None => None,
+            Some(&mut Slot::Vacant(_)) => None,
+            Some(&mut Slot::Occupied(ref mut object)) => Some(object),
+        }
+    }
+
+    /// Returns mutable references to the objects stored at two distinct indices.
+    ///
+    /// Each reference is [`None`] if the corresponding slot is vacant or out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` are equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let parent = arena.insert(vec![]);
+    /// let child = arena.insert(vec![]);
+    ///
+    /// if let (Some(p), Some(c)) = arena.get2_mut(parent, child) {
+    ///     p.push(child);
+    ///     c.push(parent);
+    /// }
+    /// assert_eq!(arena[parent], [child]);
+    /// assert_eq!(arena[child], [parent]);
+    ///
+    /// assert_eq!(arena.get2_mut(child, 5), (Some(&mut vec![parent]), None));
+    /// ```
+    pub fn get2_mut(&mut self, a: usize, b: usize) -> (Option<&mut T>, Option<&mut T>) {
+        assert!(a != b, "the two indices must be distinct");
+
+        let (lo, hi) = (a.min(b), a.max(b));
+        let split = hi.min(self.slots.len());
+        let (left, right) = self.slots.split_at_mut(split);
+
+        let lo = match left.get_mut(lo) {
+            Some(Slot::Occupied(object)) => Some(object),
+            _ => None,
+        };
+        let hi = match right.first_mut() {
+            Some(Slot::Occupied(object)) => Some(object),
+            _ => None,
+        };
+
+        if a < b {
+            (lo, hi)
+        } else {
+            (hi, lo)
+        }
+    }
+
+    /// Returns mutable references to the objects stored at `N` distinct indices.
+    ///
+    /// The references are returned in the same order as `indices`. If any of the indices are
+    /// equal, or any slot is vacant or out of bounds, [`None`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..5).collect();
+    ///
+    /// if let Some([a, b, c]) = arena.get_many_mut([4, 0, 2]) {
+    ///     *a += 10;
+    ///     *b += 20;
+    ///     *c += 30;
+    /// }
+    /// assert_eq!(arena.iter().map(|(_, &v)| v).collect::<Vec<_>>(), [20, 1, 32, 3, 14]);
+    ///
+    /// assert!(arena.get_many_mut([1, 1]).is_none());
+    /// assert!(arena.get_many_mut([1, 7]).is_none());
+    /// ```
+    pub fn get_many_mut<const N: usize>(&mut self, indices: [usize; N]) -> Option<[&mut T; N]> {
+        // Visit the indices in ascending order so that the slots can be split off one by one.
+        let mut order = [0; N];
+        for (i, position) in order.iter_mut().enumerate() {
+            *position = i;
+        }
+        order.sort_unstable_by_key(|&position| indices[position]);
+
+        let mut objects = [(); N].map(|()| None);
+        let mut rest = &mut self.slots[..];
+        let mut start = 0;
+
+        for position in order {
+            let index = indices[position];
+            if index < start || index - start >= rest.len() {
+                return None;
+            }
+
+            let (visited, tail) = mem::take(&mut rest).split_at_mut(index - start + 1);
+            rest = tail;
+            start = index + 1;
+
+            match visited.last_mut() {
+                Some(Slot::Occupied(object)) => objects[position] = Some(object),
+                _ => return None,
+            }
+        }
+
+        Some(objects.map(|object| object.unwrap()))
+    }
+
+    /// Returns a mutable reference to the object at `index` together with a shared view of every
+    /// other slot.
+    ///
+    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (1..=4).collect();
+    ///
+    /// let (object, rest) = arena.split_index_mut(2).unwrap();
+    /// *object = rest.iter().map(|(_, &v)| v).sum();
+    /// assert_eq!(rest.get(2), None);
+    ///
+    /// assert_eq!(arena[2], 7);
+    /// ```
+    pub fn split_index_mut(&mut self, index: usize) -> Option<(&mut T, ArenaView<'_, T>)> {
+        if index >= self.slots.len() {
+            return None;
+        }
+
+        let len = self.len;
+        let (left, right) = self.slots.split_at_mut(index);
+        let (slot, right) = right.split_first_mut()?;
+        match slot {
+            Slot::Vacant(_) => None,
+            Slot::Occupied(object) => Some((
+                object,
+                ArenaView {
+                    left,
+                    right,
+                    len: len - 1,
+                },
+            )),
+        }
+    }
+
+    /// Looks up every index in `indices` and returns the results in the same order.
+    ///
+    /// Each entry is [`None`] if the corresponding slot is vacant or out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// let b = arena.insert("b");
+    /// arena.remove(a);
+    ///
+    /// assert_eq!(arena.gather(&[b, a, 7, b]), [Some(&"b"), None, None, Some(&"b")]);
+    /// ```
+    pub fn gather(&self, indices: &[usize]) -> Vec<Option<&T>> {
+        let slots = &self.slots[..];
+        indices
+            .iter()
+            .map(|&index| match slots.get(index) {
+                Some(Slot::Occupied(object)) => Some(object),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Stores every `(index, object)` pair from `updates` into its occupied slot, replacing the
+    /// old object.
+    ///
+    /// Updates targeting a vacant or out-of-bounds slot are not applied and are returned instead,
+    /// in the order they were given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    /// arena.remove(b);
+    ///
+    /// let rejected = arena.scatter(vec![(a, 10), (b, 20), (9, 90)]);
+    /// assert_eq!(rejected, [(b, 20), (9, 90)]);
+    /// assert_eq!(arena[a], 10);
+    /// ```
+    pub fn scatter<I>(&mut self, updates: I) -> Vec<(usize, T)>
+    where
+        I: IntoIterator<Item = (usize, T)>,
+    {
+        let mut rejected = Vec::new();
+        for (index, object) in updates {
+            match self.slots.get_mut(index) {
+                Some(Slot::Occupied(slot)) => *slot = object,
+                _ => rejected.push((index, object)),
+            }
+        }
+        rejected
+    }
+
+    /// Swaps two objects in the arena.
+    ///
+    /// The two indices are `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the indices is out of bounds or any of the slots is vacant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(7);
+    /// let b = arena.insert(8);
+    ///
+    /// arena.swap(a, b);
+    /// assert_eq!(arena.get(a), Some(&8));
+    /// assert_eq!(arena.get(b), Some(&7));
+    /// ```
+    #[inline]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        assert!(self.slots[a].is_occupied(), "invalid object ID");
+        assert!(self.slots[b].is_occupied(), "invalid object ID");
+
+        if a != b {
+            let (a, b) = (a.min(b), a.max(b));
+            let (l, r) = self.slots.split_at_mut(b);
+            mem::swap(&mut l[a], &mut r[0]);
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more objects to be inserted.
+    ///
+    /// The arena may reserve more space to avoid frequent reallocations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert("hello");
+    ///
+    /// arena.reserve(10);
+    /// assert!(arena.capacity() >= 11);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let vacant = self.slots.len() - self.len;
+        if additional > vacant {
+            self.slots.reserve(additional - vacant);
+        }
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more objects to be inserted.
+    ///
+    /// Note that the allocator may give the arena more space than it requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert("hello");
+    ///
+    /// arena.reserve_exact(10);
+    /// assert!(arena.capacity() >= 11);
+    /// ```
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let vacant = self.slots.len() - self.len;
+        if additional > vacant {
+            self.slots.reserve_exact(additional - vacant);
+        }
+    }
+
+    /// Tries to reserve capacity for at least `additional` more objects to be inserted.
+    ///
+    /// This is like [`reserve()`][`Arena::reserve()`], except that an error is returned instead
+    /// of panicking or aborting if the capacity overflows or the allocator reports a failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert("hello");
+    ///
+    /// assert!(arena.try_reserve(10).is_ok());
+    /// assert!(arena.capacity() >= 11);
+    ///
+    /// assert!(arena.try_reserve(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let vacant = self.slots.len() - self.len;
+        if additional > vacant {
+            self.slots.try_reserve(additional - vacant)?;
+        }
+        Ok(())
+    }
+
+    /// Tries to clone the arena, returning an error if allocating the new storage fails.
+    ///
+    /// This is like [`clone()`][`Clone::clone()`], except that an error is returned instead of
+    /// panicking or aborting if the allocator reports a failure. The clone has the same indices
+    /// and the same vacant slot order as the original.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// arena.insert("b");
+    /// arena.remove(a);
+    ///
+    /// let mut copy = arena.try_clone().unwrap();
+    /// assert_eq!(copy.len(), 1);
+    /// assert_eq!(copy[1], "b");
+    /// assert_eq!(copy.insert("c"), arena.insert("c"));
+    /// ```
+    pub fn try_clone(&self) -> Result<Self, TryReserveError>
+    where
+        T: Clone,
+    {
+        let mut slots = Vec::new();
+        slots.try_reserve_exact(self.slots.len())?;
+        slots.extend(self.slots.iter().cloned());
+
+        Ok(Arena {
+            slots,
+            len: self.len,
+            head: self.head,
+            holes: self.holes,
+        })
+    }
+
+    /// Clones the objects at the given indices into a new arena, keeping their indices.
+    ///
+    /// All other slots in the new arena are vacant, and are reused in ascending order. Indices
+    /// of vacant or out-of-bounds slots are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let arena: Arena<_> = ["a", "b", "c", "d"].iter().copied().collect();
+    ///
+    /// let mut subset = arena.clone_subset(vec![3, 1, 7]);
+    /// assert_eq!(subset.len(), 2);
+    /// assert_eq!(subset[1], "b");
+    /// assert_eq!(subset[3], "d");
+    /// assert_eq!(subset.get(0), None);
+    ///
+    /// assert_eq!(subset.insert("e"), 0);
+    /// ```
+    pub fn clone_subset<I>(&self, indices: I) -> Self
+    where
+        T: Clone,
+        I: IntoIterator<Item = usize>,
+    {
+        let mut slots = Vec::new();
+        for index in indices {
+            if let Some(Slot::Occupied(object)) = self.slots.get(index) {
+                if index >= slots.len() {
+                    slots.resize_with(index + 1, || Slot::Vacant(!0));
+                }
+                slots[index] = Slot::Occupied(object.clone());
+            }
+        }
+        Arena::from_slots(slots)
+    }
+
+    /// Returns an iterator over occupied slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert(1);
+    /// arena.insert(2);
+    /// arena.insert(4);
+    ///
+    /// let mut iterator = arena.iter();
+    /// assert_eq!(iterator.next(), Some((0, &1)));
+    /// assert_eq!(iterator.next(), Some((1, &2)));
+    /// assert_eq!(iterator.next(), Some((2, &4)));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            slots: self.slots.iter().enumerate(),
+        }
+    }
+
+    /// Returns an iterator over occupied slots with index `start` or higher.
+    ///
+    /// This can be used to page through an arena: continue from one past the last index seen.
+    /// Objects never move unless the arena is explicitly rearranged (for example by
+    /// [`swap()`][`Arena::swap()`] or [`compact_step()`][`Arena::compact_step()`]), so a page
+    /// never skips or repeats an object that stays in the arena. Objects inserted in between may
+    /// or may not be seen, depending on whether they land before or after the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..5).collect();
+    ///
+    /// let page: Vec<_> = arena.iter_from(0).take(2).collect();
+    /// assert_eq!(page, [(0, &0), (1, &1)]);
+    /// let cursor = page.last().unwrap().0 + 1;
+    ///
+    /// arena.remove(0);
+    /// arena.remove(2);
+    ///
+    /// let page: Vec<_> = arena.iter_from(cursor).take(2).collect();
+    /// assert_eq!(page, [(3, &3), (4, &4)]);
+    /// ```
+    #[inline]
+    pub fn iter_from(&self, start: usize) -> Iter<'_, T> {
+        let mut slots = self.slots.iter().enumerate();
+        if start > 0 {
+            slots.nth(start - 1);
+        }
+        Iter { slots }
+    }
+
+    /// Returns an iterator that returns mutable references to objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert("zero".to_string());
+    /// arena.insert("one".to_string());
+    /// arena.insert("two".to_string());
+    ///
+    /// for (index, object) in arena.iter_mut() {
+    ///     *object = index.to_string() + " " + object;
+    /// }
+    ///
+    /// let mut iterator = arena.iter();
+    /// assert_eq!(iterator.next(), Some((0, &"0 zero".to_string())));
+    /// assert_eq!(iterator.next(), Some((1, &"1 one".to_string())));
+    /// assert_eq!(iterator.next(), Some((2, &"2 two".to_string())));
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            slots: self.slots.iter_mut().enumerate(),
+        }
+    }
+
+    /// Returns an iterator over occupied slots, ordered by their objects using `compare`.
+    ///
+    /// This allocates a temporary array of indices and sorts it. The sort is stable, so objects
+    /// that compare equal are yielded in index order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert(3);
+    /// arena.insert(1);
+    /// arena.insert(2);
+    ///
+    /// let mut iterator = arena.iter_sorted_by(|a, b| a.cmp(b));
+    /// assert_eq!(iterator.next(), Some((1, &1)));
+    /// assert_eq!(iterator.next(), Some((2, &2)));
+    /// assert_eq!(iterator.next(), Some((0, &3)));
+    /// assert_eq!(iterator.next(), None);
+    /// ```
+    pub fn iter_sorted_by<F>(&self, mut compare: F) -> IterSorted<'_, T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut indices: Vec<usize> = self.iter().map(|(index, _)| index).collect();
+        indices.sort_by(|&a, &b| compare(&self[a], &self[b]));
+        IterSorted {
+            arena: self,
+            indices: indices.into_iter(),
+        }
+    }
+
+    /// Computes a deterministic hash of the occupied slots and their objects.
+    ///
+    /// The result depends only on which indices are occupied and what the objects feed into
+    /// [`Hash`], not on the platform or the arena's capacity. It is the 64-bit FNV-1a hash of each
+    /// occupied index as a little-endian `u64`, followed by that object's [`Hash`] output, in
+    /// index order. Integers written by [`Hash`] implementations are fed in little-endian byte
+    /// order, with `usize` and `isize` widened to 64 bits.
+    ///
+    /// This is suitable for checksums, not for hash tables exposed to untrusted input. Note that
+    /// the [`Hash`] implementations of standard library types are not guaranteed to stay the same
+    /// across Rust versions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut a = Arena::new();
+    /// a.insert("x");
+    /// a.insert("y");
+    ///
+    /// let mut b = Arena::with_capacity(100);
+    /// b.insert("x");
+    /// b.insert("z");
+    /// assert_ne!(a.content_hash(), b.content_hash());
+    ///
+    /// b[1] = "y";
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64
+    where
+        T: Hash,
+    {
+        let mut hasher = Fnv1a::new();
+        for (index, object) in self.iter() {
+            hasher.write_u64(index as u64);
+            object.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Returns an iterator over occupied slots whose bit is set in `mask`.
+    ///
+    /// `mask` is a packed bitset in the layout of [`occupancy_words()`][`Arena::occupancy_words()`]:
+    /// bit `j` of `mask[i]` selects index `i * usize::BITS + j`. Bits for vacant or out-of-bounds
+    /// slots are ignored, and all-zero words are skipped without touching the slots they cover.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..6).collect();
+    /// arena.remove(3);
+    ///
+    /// let mask = [0b111010];
+    /// let selected: Vec<_> = arena.iter_masked(&mask).collect();
+    /// assert_eq!(selected, [(1, &1), (4, &4), (5, &5)]);
+    /// ```
+    #[inline]
+    pub fn iter_masked<'a>(&'a self, mask: &'a [usize]) -> IterMasked<'a, T> {
+        IterMasked {
+            slots: &self.slots,
+            bits: MaskBits::new(mask),
+        }
+    }
+
+    /// Returns an iterator over mutable references to occupied slots whose bit is set in `mask`.
+    ///
+    /// The mask has the same layout as in [`iter_masked()`][`Arena::iter_masked()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..4).collect();
+    ///
+    /// for (_, object) in arena.iter_masked_mut(&[0b1010]) {
+    ///     *object *= 10;
+    /// }
+    /// assert_eq!(arena.iter().map(|(_, &v)| v).collect::<Vec<_>>(), [0, 10, 2, 30]);
+    /// ```
+    #[inline]
+    pub fn iter_masked_mut<'a>(&'a mut self, mask: &'a [usize]) -> IterMaskedMut<'a, T> {
+        IterMaskedMut {
+            slots: &mut self.slots,
+            start: 0,
+            bits: MaskBits::new(mask),
+        }
+    }
+
+    /// Returns an iterator over maximal runs of consecutive occupied slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..6).collect();
+    /// arena.remove(2);
+    /// arena.remove(3);
+    /// arena.remove(5);
+    ///
+    /// let runs: Vec<_> = arena.occupied_ranges().collect();
+    /// assert_eq!(runs, [0..2, 4..5]);
+    /// ```
+    #[inline]
+    pub fn occupied_ranges(&self) -> Ranges<'_, T> {
+        Ranges {
+            slots: &self.slots,
+            start: 0,
+            occupied: true,
+        }
+    }
+
+    /// Returns an iterator over maximal runs of consecutive vacant slots.
+    ///
+    /// Only slots below the current slot count are considered, so there is no trailing run
+    /// extending to infinity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..6).collect();
+    /// arena.remove(2);
+    /// arena.remove(3);
+    /// arena.remove(5);
+    ///
+    /// let runs: Vec<_> = arena.vacant_ranges().collect();
+    /// assert_eq!(runs, [2..4, 5..6]);
+    /// ```
+    #[inline]
+    pub fn vacant_ranges(&self) -> Ranges<'_, T> {
+        Ranges {
+            slots: &self.slots,
+            start: 0,
+            occupied: false,
+        }
+    }
+
+    /// Returns an iterator over the occupancy of slots, packed into words of bits.
+    ///
+    /// Bit `j` of the `i`-th word is set if the slot at index `i * usize::BITS + j` is occupied.
+    /// Bits past the last slot are zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// for i in 0..4 {
+    ///     arena.insert(i);
+    /// }
+    /// arena.remove(1);
+    ///
+    /// let words: Vec<usize> = arena.occupancy_words().collect();
+    /// assert_eq!(words, [0b1101]);
+    /// ```
+    #[inline]
+    pub fn occupancy_words(&self) -> OccupancyWords<'_, T> {
+        OccupancyWords {
+            chunks: self.slots.chunks(WORD_BITS),
+        }
+    }
+
+    /// Returns a displayable dump of the arena's internal state, for debugging.
+    ///
+    /// The dump lists every slot with its state, followed by the chain of vacant slots in the
+    /// order [`insert()`][`Arena::insert()`] will reuse them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// for i in 0..4 {
+    ///     arena.insert(i);
+    /// }
+    /// arena.remove(1);
+    /// arena.remove(3);
+    ///
+    /// assert_eq!(
+    ///     arena.dump_state().to_string(),
+    ///     "len: 2, slots: 4, capacity: 4\n\
+    ///      0: occupied\n\
+    ///      1: vacant -> end\n\
+    ///      2: occupied\n\
+    ///      3: vacant -> 1\n\
+    ///      vacant list: 3 -> 1 -> end\n",
+    /// );
+    /// ```
+    #[inline]
+    pub fn dump_state(&self) -> DumpState<'_, T> {
+        DumpState { arena: self }
+    }
+
+    /// Moves up to `max_moves` objects toward the front of the arena and returns `true` if it is
+    /// now [dense][`Arena::is_dense()`].
+    ///
+    /// Each step moves the object at the highest occupied index into a vacant slot below
+    /// [`len()`][`Arena::len()`], so no object is moved twice, and calls
+    /// `f(old_index, new_index)` so that references to it can be updated. Vacant slots left at the
+    /// end of the arena are dropped along the way, so once this returns `true` there are no vacant
+    /// slots at all. The capacity is kept.
+    ///
+    /// Calling this repeatedly spreads compaction out over time. The first call, and the first
+    /// call after the arena was modified in between, reorders the list of vacant slots in time
+    /// proportional to the number of slots. Every other call takes `O(max_moves)` amortized time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..6).collect();
+    /// arena.remove(0);
+    /// arena.remove(2);
+    ///
+    /// let mut moves = Vec::new();
+    /// assert!(!arena.compact_step(1, |from, to| moves.push((from, to))));
+    /// assert!(arena.compact_step(1, |from, to| moves.push((from, to))));
+    /// assert_eq!(moves, [(5, 2), (4, 0)]);
+    ///
+    /// assert_eq!(arena[0], 4);
+    /// assert_eq!(arena[2], 5);
+    /// assert!(arena.compact_step(1, |_, _| unreachable!()));
+    /// ```
+    pub fn compact_step<F>(&mut self, max_moves: usize, mut f: F) -> bool
+    where
+        F: FnMut(usize, usize),
+    {
+        let mut moves = 0;
+
+        loop {
+            // Drop vacant slots from the end of the array while they're at the head of the list.
+            while self.head != !0 && self.head == self.slots.len() - 1 {
+                match self.slots.pop() {
+                    Some(Slot::Vacant(next)) => self.head = next,
+                    _ => unreachable!(),
+                }
+            }
+            if self.head == !0 {
+                return true;
+            }
+
+            // The head of the list must be either the last slot, or a vacant slot below `len`
+            // that the last object can move into. Otherwise, the list is out of order.
+            let last = self.slots.len() - 1;
+            if !self.slots[last].is_occupied() || self.head >= self.len {
+                self.link_for_compaction();
+                continue;
+            }
+            if moves == max_moves {
+                return false;
+            }
+
+            let index = self.head;
+            match self.slots.pop() {
+                Some(Slot::Occupied(object)) => {
+                    match mem::replace(&mut self.slots[index], Slot::Occupied(object)) {
+                        Slot::Vacant(next) => self.head = next,
+                        Slot::Occupied(_) => unreachable!(),
+                    }
+                }
+                _ => unreachable!(),
+            }
+            self.holes -= 1;
+            f(last, index);
+            moves += 1;
+        }
+    }
+
+    /// Moves objects toward the front of the arena until it is dense, then drops all vacant
+    /// slots.
+    ///
+    /// Every moved object goes from the highest occupied index into a vacant slot below
+    /// [`len()`][`Arena::len()`], and `f(old_index, new_index)` is called so that references to it
+    /// can be updated. Afterwards, objects occupy indices `0..len()`. The capacity is kept; call
+    /// [`shrink_to_fit()`][`Arena::shrink_to_fit()`] to release it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..6).collect();
+    /// arena.remove(0);
+    /// arena.remove(2);
+    ///
+    /// let mut moves = Vec::new();
+    /// arena.compact(|from, to| moves.push((from, to)));
+    /// assert_eq!(moves, [(5, 2), (4, 0)]);
+    ///
+    /// assert!(arena.is_dense());
+    /// assert_eq!(arena.insert(6), 4);
+    /// ```
+    pub fn compact<F>(&mut self, f: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        self.compact_step(usize::MAX, f);
+    }
+
+    /// Reorders the list of vacant slots so that they are reused in ascending index order.
+    ///
+    /// Removed slots are normally reused most recent first. After removing many objects, this
+    /// makes subsequent inserts fill the lowest indices first, keeping new objects together. It
+    /// takes time proportional to the number of slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<i32> = (0..5).collect();
+    /// arena.remove(1);
+    /// arena.remove(3);
+    /// arena.remove(2);
+    /// assert_eq!(arena.next_vacant(), 2);
+    ///
+    /// arena.normalize_free_list();
+    /// assert_eq!(arena.insert(10), 1);
+    /// assert_eq!(arena.insert(20), 2);
+    /// assert_eq!(arena.insert(30), 3);
+    /// ```
+    pub fn normalize_free_list(&mut self) {
+        self.relink();
+    }
+
+    /// Rearranges the objects so that the object at `order[i]` moves to index `i`.
+    ///
+    /// `order` must list every occupied index exactly once. Afterwards the arena is
+    /// [dense][`Arena::is_dense()`], with objects laid out in the given order, and the new index
+    /// of the object previously at `order[i]` is `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` doesn't contain every occupied index exactly once, or contains a vacant or
+    /// out-of-bounds index. The arena is left unchanged in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// let b = arena.insert("b");
+    /// let c = arena.insert("c");
+    /// arena.remove(b);
+    ///
+    /// arena.apply_permutation(&[c, a]);
+    /// assert_eq!(arena[0], "c");
+    /// assert_eq!(arena[1], "a");
+    /// assert_eq!(arena.insert("d"), 2);
+    /// ```
+    pub fn apply_permutation(&mut self, order: &[usize]) {
+        assert_eq!(
+            order.len(),
+            self.len,
+            "`order` must list every occupied index"
+        );
+
+        let mut listed = vec![false; self.slots.len()];
+        for &index in order {
+            let valid = matches!(self.slots.get(index), Some(Slot::Occupied(_)));
+            assert!(
+                valid,
+                "`order` contains vacant or out-of-bounds index {}",
+                index
+            );
+            assert!(!listed[index], "`order` contains index {} twice", index);
+            listed[index] = true;
+        }
+
+        let mut old = mem::take(&mut self.slots);
+        self.slots.reserve_exact(old.capacity());
+        for &index in order {
+            self.slots
+                .push(mem::replace(&mut old[index], Slot::Vacant(!0)));
+        }
+        self.head = !0;
+        self.holes = 0;
+    }
+
+    /// Shrinks the capacity of the arena as much as possible.
+    ///
+    /// Vacant slots after the last occupied one are released first, so an arena whose highest
+    /// indices have been removed gives that memory back too. If any were released, the remaining
+    /// vacant slots are relinked so that lower indices are reused first.
+    ///
+    /// It will drop down as close as possible to the length but the allocator may still inform
+    /// the arena that there is space for a few more elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(10);
+    /// arena.insert("first".to_string());
+    /// arena.insert("second".to_string());
+    /// arena.insert("third".to_string());
+    /// assert_eq!(arena.capacity(), 10);
+    /// arena.shrink_to_fit();
+    /// assert!(arena.capacity() >= 3);
+    ///
+    /// arena.remove(2);
+    /// arena.shrink_to_fit();
+    /// assert!(arena.capacity() >= 2);
+    /// assert_eq!(arena.insert("fourth".to_string()), 2);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let end = self
+            .slots
+            .iter()
+            .rposition(Slot::is_occupied)
+            .map_or(0, |last| last + 1);
+        if end < self.slots.len() {
+            self.slots.truncate(end);
+            self.relink();
+        }
+        self.slots.shrink_to_fit();
+    }
+
+    /// Constructs an arena from slots, linking the vacant ones so that lower indices are reused
+    /// first.
+    ///
+    /// The links stored in vacant slots are ignored.
+    fn from_slots(slots: Vec<Slot<T>>) -> Self {
+        let mut arena = Arena {
+            slots,
+            len: 0,
+            head: !0,
+            holes: 0,
+        };
+        arena.relink();
+        arena
+    }
+
+    /// Relinks vacant slots in the order [`compact_step()`][`Arena::compact_step()`] consumes
+    /// them.
+    ///
+    /// Compaction repeatedly drops the last slot if it is vacant, or else moves the last object
+    /// into the lowest vacant slot. This simulates that process and links vacant slots in the
+    /// order they are encountered, so that each one is at the head of the list when needed.
+    fn link_for_compaction(&mut self) {
+        self.head = !0;
+        let mut tail = !0;
+        let mut link = |slots: &mut [Slot<T>], head: &mut usize, index: usize| {
+            if tail == !0 {
+                *head = index;
+            } else {
+                slots[tail] = Slot::Vacant(index);
+            }
+            slots[index] = Slot::Vacant(!0);
+            tail = index;
+        };
+
+        // Slots below `lo` and at or above `hi` have been accounted for.
+        let mut lo = 0;
+        let mut hi = self.slots.len();
+        loop {
+            while hi > lo && !self.slots[hi - 1].is_occupied() {
+                hi -= 1;
+                link(&mut self.slots, &mut self.head, hi);
+            }
+            while lo < hi && self.slots[lo].is_occupied() {
+                lo += 1;
+            }
+            if lo == hi {
+                break;
+            }
+
+            // The object at `hi - 1` will move into `lo`.
+            link(&mut self.slots, &mut self.head, lo);
+            lo += 1;
+            hi -= 1;
+        }
+    }
+
+    /// Recounts occupied slots and holes, and relinks vacant ones so that lower indices are
+    /// reused first.
+    ///
+    /// The links stored in vacant slots are ignored.
+    fn relink(&mut self) {
+        self.len = 0;
+        self.head = !0;
+        for (index, slot) in self.slots.iter_mut().enumerate().rev() {
+            match slot {
+                Slot::Vacant(next) => {
+                    *next = self.head;
+                    self.head = index;
+                }
+                Slot::Occupied(_) => self.len += 1,
+            }
+        }
+        self.holes = self.slots[..self.len]
+            .iter()
+            .filter(|slot| !slot.is_occupied())
+            .count();
+    }
+
+    /// Updates the hole count after the slot at `index` was filled and `len` incremented.
+    fn count_occupied(&mut self, index: usize) {
+        let old_len = self.len - 1;
+        if index < old_len {
+            self.holes -= 1;
+        }
+        if !self.slots[old_len].is_occupied() {
+            self.holes += 1;
+        }
+    }
+
+    /// Updates the hole count after the slot at `index` was vacated and `len` decremented.
+    fn count_vacated(&mut self, index: usize) {
+        if index < self.len {
+            self.holes += 1;
+        }
+        if index != self.len && !self.slots[self.len].is_occupied() {
+            self.holes -= 1;
+        }
+    }
+}
+
+impl<T> fmt::Debug for Arena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Arena {{ ... }}")
+    }
+}
+
+impl<T> Index<usize> for Arena<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
         self.get(index).expect("vacant slot at `index`")
     }
-}
+}
+
+impl<T> IndexMut<usize> for Arena<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("vacant slot at `index`")
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena::new()
+    }
+}
+
+impl<T: Clone> Clone for Arena<T> {
+    fn clone(&self) -> Self {
+        Arena {
+            slots: self.slots.clone(),
+            len: self.len,
+            head: self.head,
+            holes: self.holes,
+        }
+    }
+}
+
+impl<T> TryFrom<Arena<T>> for Vec<T> {
+    type Error = FragmentedError<T>;
+
+    /// Converts an arena into a vector, placing each object at its arena index.
+    ///
+    /// This succeeds only if all slots `0..arena.len()` are occupied. Otherwise the arena is
+    /// handed back inside the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// arena.insert(2);
+    /// arena.insert(3);
+    ///
+    /// arena.remove(a);
+    /// let err = Vec::try_from(arena).unwrap_err();
+    /// assert_eq!(err.first_vacant(), a);
+    ///
+    /// let mut arena = err.into_arena();
+    /// arena.insert(4);
+    /// assert_eq!(Vec::try_from(arena).unwrap(), [4, 2, 3]);
+    /// ```
+    fn try_from(mut arena: Arena<T>) -> Result<Self, Self::Error> {
+        if !arena.is_dense() {
+            let first_vacant = arena
+                .slots
+                .iter()
+                .position(|slot| !slot.is_occupied())
+                .unwrap();
+            return Err(FragmentedError {
+                arena,
+                first_vacant,
+            });
+        }
+
+        arena.slots.truncate(arena.len);
+        Ok(arena
+            .slots
+            .into_iter()
+            .map(|slot| match slot {
+                Slot::Vacant(_) => unreachable!(),
+                Slot::Occupied(object) => object,
+            })
+            .collect())
+    }
+}
+
+/// The error returned when converting an [`Arena`] with holes into a [`Vec`].
+pub struct FragmentedError<T> {
+    arena: Arena<T>,
+    first_vacant: usize,
+}
+
+impl<T> FragmentedError<T> {
+    /// Returns the lowest index of a vacant slot in the arena.
+    #[inline]
+    pub fn first_vacant(&self) -> usize {
+        self.first_vacant
+    }
+
+    /// Returns the arena that failed to convert.
+    #[inline]
+    pub fn into_arena(self) -> Arena<T> {
+        self.arena
+    }
+}
+
+impl<T> fmt::Debug for FragmentedError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FragmentedError")
+            .field("first_vacant", &self.first_vacant)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for FragmentedError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arena has a vacant slot at index {} below its length {}",
+            self.first_vacant, self.arena.len
+        )
+    }
+}
+
+/// The error returned by [`Arena::insert_or_err()`] when the arena could not grow.
+pub struct InsertError<T> {
+    object: T,
+    error: TryReserveError,
+}
+
+impl<T> InsertError<T> {
+    /// Returns the allocation error that prevented the insertion.
+    #[inline]
+    pub fn reserve_error(&self) -> &TryReserveError {
+        &self.error
+    }
+
+    /// Returns the object that could not be inserted.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.object
+    }
+}
+
+impl<T> fmt::Debug for InsertError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InsertError")
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for InsertError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to grow the arena: {}", self.error)
+    }
+}
+
+/// A handle to a vacant slot in an [`Arena`], created by [`Arena::vacant_entry()`].
+pub struct VacantEntry<'a, T> {
+    arena: &'a mut Arena<T>,
+    index: usize,
+}
+
+impl<'a, T> VacantEntry<'a, T> {
+    /// Returns the index the object will be stored at.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Stores `object` in the slot and returns a mutable reference to it.
+    #[inline]
+    pub fn insert(self, object: T) -> &'a mut T {
+        let (index, object) = self.arena.insert_and_get_mut(object);
+        debug_assert_eq!(index, self.index);
+        object
+    }
+}
+
+impl<'a, T> fmt::Debug for VacantEntry<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantEntry")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// A shared view of every slot in an [`Arena`] except one, created by
+/// [`Arena::split_index_mut()`].
+pub struct ArenaView<'a, T> {
+    /// Slots before the excluded one.
+    left: &'a [Slot<T>],
+
+    /// Slots after the excluded one.
+    right: &'a [Slot<T>],
+
+    /// Number of occupied slots in the view.
+    len: usize,
+}
+
+impl<'a, T> ArenaView<'a, T> {
+    /// Returns the number of objects in the view.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view contains no objects.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the object stored at `index`.
+    ///
+    /// If the slot is vacant, out of bounds, or the one excluded from the view, [`None`] will be
+    /// returned.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        let slot = if index < self.left.len() {
+            self.left.get(index)
+        } else {
+            self.right.get((index - self.left.len()).checked_sub(1)?)
+        };
+        match slot {
+            Some(Slot::Occupied(object)) => Some(object),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the occupied slots in the view.
+    #[inline]
+    pub fn iter(&self) -> ViewIter<'a, T> {
+        ViewIter {
+            left: Iter {
+                slots: self.left.iter().enumerate(),
+            },
+            right: Iter {
+                slots: self.right.iter().enumerate(),
+            },
+            offset: self.left.len() + 1,
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for ArenaView<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ArenaView {{ ... }}")
+    }
+}
+
+/// An iterator over references to the occupied slots in an [`ArenaView`].
+pub struct ViewIter<'a, T> {
+    left: Iter<'a, T>,
+    right: Iter<'a, T>,
+
+    /// Index of the first slot in `right`.
+    offset: usize,
+}
+
+impl<'a, T> Iterator for ViewIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.left.next() {
+            Some(item) => Some(item),
+            None => {
+                let offset = self.offset;
+                self.right
+                    .next()
+                    .map(|(index, object)| (index + offset, object))
+            }
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for ViewIter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ViewIter {{ ... }}")
+    }
+}
+
+/// A tentatively inserted object, created by [`Arena::insert_tentative()`].
+///
+/// Unless [`commit()`][`TentativeInsert::commit()`] is called, dropping the guard removes the
+/// object from the arena.
+pub struct TentativeInsert<'a, T> {
+    arena: &'a mut Arena<T>,
+    index: usize,
+    committed: bool,
+}
+
+impl<'a, T> TentativeInsert<'a, T> {
+    /// Returns the index the object is stored at.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Keeps the object in the arena and returns its index.
+    #[inline]
+    pub fn commit(mut self) -> usize {
+        self.committed = true;
+        self.index
+    }
+}
+
+impl<'a, T> Deref for TentativeInsert<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.arena[self.index]
+    }
+}
+
+impl<'a, T> DerefMut for TentativeInsert<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.arena[self.index]
+    }
+}
+
+impl<'a, T> Drop for TentativeInsert<'a, T> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.arena.remove(self.index);
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for TentativeInsert<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TentativeInsert")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// A handle to an [`Arena`] that never allocates, created by [`Arena::fixed()`].
+///
+/// Only operations that work within the existing slot array are available.
+pub struct FixedArena<'a, T> {
+    arena: &'a mut Arena<T>,
+}
+
+impl<'a, T> FixedArena<'a, T> {
+    /// Returns the number of slots in the arena.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.arena.capacity()
+    }
 
-impl<T> IndexMut<usize> for Arena<T> {
+    /// Returns the number of occupied slots in the arena.
     #[inline]
-    fn index_mut(&mut self, index: usize) -> &mut T {
-        self.get_mut(index).expect("vacant slot at `index`")
+    pub fn len(&self) -> usize {
+        self.arena.len()
     }
-}
 
-impl<T> Default for Arena<T> {
-    fn default() -> Self {
-        Arena::new()
+    /// Returns `true` if all slots are vacant.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Inserts an object into a vacant slot or into spare capacity and returns its index.
+    ///
+    /// If the arena is full, the object is handed back instead.
+    #[inline]
+    pub fn insert(&mut self, object: T) -> Result<usize, T> {
+        self.arena.insert_within_capacity(object)
+    }
+
+    /// Removes the object stored at `index` from the arena and returns it.
+    ///
+    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        self.arena.remove(index)
+    }
+
+    /// Returns a reference to the object stored at `index`.
+    ///
+    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.arena.get(index)
+    }
+
+    /// Returns a mutable reference to the object stored at `index`.
+    ///
+    /// If the slot is vacant or `index` is out of bounds, [`None`] will be returned.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.arena.get_mut(index)
+    }
+
+    /// Returns an iterator over the occupied slots.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.arena.iter()
+    }
+
+    /// Returns an iterator that returns mutable references to objects.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.arena.iter_mut()
     }
 }
 
-impl<T: Clone> Clone for Arena<T> {
-    fn clone(&self) -> Self {
-        Arena {
-            slots: self.slots.clone(),
-            len: self.len,
-            head: self.head,
-        }
+impl<'a, T> fmt::Debug for FixedArena<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FixedArena {{ ... }}")
     }
 }
 
@@ -640,13 +2681,24 @@ impl<T> Iterator for IntoIter<T> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some((index, slot)) = self.slots.next() {
+        for (index, slot) in self.slots.by_ref() {
             if let Slot::Occupied(object) = slot {
                 return Some((index, object));
             }
         }
         None
     }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.slots.fold(init, |acc, (index, slot)| match slot {
+            Slot::Vacant(_) => acc,
+            Slot::Occupied(object) => f(acc, (index, object)),
+        })
+    }
 }
 
 impl<T> IntoIterator for Arena<T> {
@@ -678,6 +2730,69 @@ impl<T> fmt::Debug for IntoIter<T> {
     }
 }
 
+/// A draining iterator over the occupied slots in an [`Arena`], created by [`Arena::drain()`].
+pub struct Drain<'a, T> {
+    slots: iter::Enumerate<vec::Drain<'a, Slot<T>>>,
+}
+
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = (usize, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, slot) in self.slots.by_ref() {
+            if let Slot::Occupied(object) = slot {
+                return Some((index, object));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> fmt::Debug for Drain<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Drain {{ ... }}")
+    }
+}
+
+/// An iterator that removes the objects matching a closure from an [`Arena`], created by
+/// [`Arena::extract_if()`].
+pub struct ExtractIf<'a, T, F> {
+    arena: &'a mut Arena<T>,
+
+    /// Index of the next slot to visit.
+    index: usize,
+
+    f: F,
+}
+
+impl<'a, T, F> Iterator for ExtractIf<'a, T, F>
+where
+    F: FnMut(usize, &mut T) -> bool,
+{
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.arena.slots.len() {
+            let index = self.index;
+            self.index += 1;
+
+            if let Slot::Occupied(object) = &mut self.arena.slots[index] {
+                if (self.f)(index, object) {
+                    return self.arena.remove(index).map(|object| (index, object));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T, F> fmt::Debug for ExtractIf<'a, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ExtractIf {{ ... }}")
+    }
+}
+
 /// An iterator over references to the occupied slots in an [`Arena`].
 pub struct Iter<'a, T> {
     slots: iter::Enumerate<slice::Iter<'a, Slot<T>>>,
@@ -688,13 +2803,24 @@ impl<'a, T> Iterator for Iter<'a, T> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some((index, slot)) = self.slots.next() {
+        for (index, slot) in self.slots.by_ref() {
             if let Slot::Occupied(ref object) = *slot {
                 return Some((index, object));
             }
         }
         None
     }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.slots.fold(init, |acc, (index, slot)| match slot {
+            Slot::Vacant(_) => acc,
+            Slot::Occupied(object) => f(acc, (index, object)),
+        })
+    }
 }
 
 impl<'a, T> IntoIterator for &'a Arena<T> {
@@ -723,13 +2849,24 @@ impl<'a, T> Iterator for IterMut<'a, T> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some((index, slot)) = self.slots.next() {
+        for (index, slot) in self.slots.by_ref() {
             if let Slot::Occupied(ref mut object) = *slot {
                 return Some((index, object));
             }
         }
         None
     }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.slots.fold(init, |acc, (index, slot)| match slot {
+            Slot::Vacant(_) => acc,
+            Slot::Occupied(object) => f(acc, (index, object)),
+        })
+    }
 }
 
 impl<'a, T> IntoIterator for &'a mut Arena<T> {
@@ -747,3 +2884,324 @@ impl<'a, T> fmt::Debug for IterMut<'a, T> {
         write!(f, "IterMut {{ ... }}")
     }
 }
+
+/// An iterator over references to the occupied slots in an [`Arena`], ordered by their objects.
+pub struct IterSorted<'a, T> {
+    arena: &'a Arena<T>,
+    indices: vec::IntoIter<usize>,
+}
+
+impl<'a, T> Iterator for IterSorted<'a, T> {
+    type Item = (usize, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let arena = self.arena;
+        self.indices.next().map(|index| (index, &arena[index]))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterSorted<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let arena = self.arena;
+        self.indices.next_back().map(|index| (index, &arena[index]))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterSorted<'a, T> {}
+
+impl<'a, T> fmt::Debug for IterSorted<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IterSorted {{ ... }}")
+    }
+}
+
+/// The indices of set bits in a packed bitset, in ascending order.
+struct MaskBits<'a> {
+    words: iter::Enumerate<slice::Iter<'a, usize>>,
+    base: usize,
+    word: usize,
+}
+
+impl<'a> MaskBits<'a> {
+    fn new(mask: &'a [usize]) -> Self {
+        MaskBits {
+            words: mask.iter().enumerate(),
+            base: 0,
+            word: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for MaskBits<'a> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            let (i, &word) = self.words.next()?;
+            self.base = i * WORD_BITS;
+            self.word = word;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(self.base + bit)
+    }
+}
+
+/// An iterator over references to the occupied slots in an [`Arena`] selected by a bitmask.
+pub struct IterMasked<'a, T> {
+    slots: &'a [Slot<T>],
+    bits: MaskBits<'a>,
+}
+
+impl<'a, T> Iterator for IterMasked<'a, T> {
+    type Item = (usize, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for index in &mut self.bits {
+            match self.slots.get(index)? {
+                Slot::Vacant(_) => {}
+                Slot::Occupied(object) => return Some((index, object)),
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> fmt::Debug for IterMasked<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IterMasked {{ ... }}")
+    }
+}
+
+/// An iterator over mutable references to the occupied slots in an [`Arena`] selected by a
+/// bitmask.
+pub struct IterMaskedMut<'a, T> {
+    /// Slots not yet visited.
+    slots: &'a mut [Slot<T>],
+
+    /// Index of the first slot in `slots`.
+    start: usize,
+
+    bits: MaskBits<'a>,
+}
+
+impl<'a, T> Iterator for IterMaskedMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for index in &mut self.bits {
+            if index - self.start >= self.slots.len() {
+                self.slots = &mut [];
+                return None;
+            }
+
+            // Split off everything up to and including this slot so that it can be borrowed for
+            // the iterator's whole lifetime.
+            let slots = mem::take(&mut self.slots);
+            let (visited, rest) = slots.split_at_mut(index - self.start + 1);
+            self.slots = rest;
+            self.start = index + 1;
+
+            if let Some(Slot::Occupied(object)) = visited.last_mut() {
+                return Some((index, object));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> fmt::Debug for IterMaskedMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IterMaskedMut {{ ... }}")
+    }
+}
+
+/// An iterator over maximal runs of occupied or vacant slots in an [`Arena`].
+pub struct Ranges<'a, T> {
+    slots: &'a [Slot<T>],
+    start: usize,
+    occupied: bool,
+}
+
+impl<'a, T> Iterator for Ranges<'a, T> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let occupied = self.occupied;
+        let slots = &self.slots[self.start..];
+
+        let skip = slots.iter().position(|s| s.is_occupied() == occupied)?;
+        let len = slots[skip..]
+            .iter()
+            .position(|s| s.is_occupied() != occupied)
+            .unwrap_or(slots.len() - skip);
+
+        let start = self.start + skip;
+        self.start = start + len;
+        Some(start..self.start)
+    }
+}
+
+impl<'a, T> iter::FusedIterator for Ranges<'a, T> {}
+
+impl<'a, T> fmt::Debug for Ranges<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ranges {{ ... }}")
+    }
+}
+
+/// An iterator over the occupancy of slots in an [`Arena`], packed into words of bits.
+pub struct OccupancyWords<'a, T> {
+    chunks: slice::Chunks<'a, Slot<T>>,
+}
+
+impl<'a, T> Iterator for OccupancyWords<'a, T> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| slot.is_occupied())
+                .fold(0, |word, (bit, _)| word | 1 << bit)
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for OccupancyWords<'a, T> {}
+
+impl<'a, T> fmt::Debug for OccupancyWords<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OccupancyWords {{ ... }}")
+    }
+}
+
+/// A dump of an [`Arena`]'s internal state, created by [`Arena::dump_state()`].
+pub struct DumpState<'a, T> {
+    arena: &'a Arena<T>,
+}
+
+impl<'a, T> fmt::Display for DumpState<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arena = self.arena;
+        writeln!(
+            f,
+            "len: {}, slots: {}, capacity: {}",
+            arena.len,
+            arena.slots.len(),
+            arena.slots.capacity()
+        )?;
+
+        for (index, slot) in arena.slots.iter().enumerate() {
+            match slot {
+                Slot::Vacant(next) if *next == !0 => writeln!(f, "{}: vacant -> end", index)?,
+                Slot::Vacant(next) => writeln!(f, "{}: vacant -> {}", index, next)?,
+                Slot::Occupied(_) => writeln!(f, "{}: occupied", index)?,
+            }
+        }
+
+        // Walk the vacant list, but don't trust it: stop on bad links and cycles.
+        write!(f, "vacant list:")?;
+        let mut index = arena.head;
+        for _ in 0..=arena.slots.len() {
+            if index == !0 {
+                return writeln!(f, " end");
+            }
+            write!(f, " {} ->", index)?;
+            index = match arena.slots.get(index) {
+                Some(Slot::Vacant(next)) => *next,
+                Some(Slot::Occupied(_)) => return writeln!(f, " (occupied slot)"),
+                None => return writeln!(f, " (out of bounds)"),
+            };
+        }
+        writeln!(f, " (cycle)")
+    }
+}
+
+impl<'a, T> fmt::Debug for DumpState<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DumpState {{ ... }}")
+    }
+}
+
+/// The 64-bit FNV-1a hasher behind [`Arena::content_hash()`].
+///
+/// Integers are hashed in little-endian byte order, and `usize`/`isize` are widened to 64 bits,
+/// so the result is the same on every platform.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64);
+    }
+}
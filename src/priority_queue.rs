@@ -0,0 +1,362 @@
+//! A priority queue with stable handles, built on an arena.
+
+use alloc::fmt;
+use alloc::vec::Vec;
+
+use crate::Arena;
+
+/// An entry in the priority queue.
+struct Entry<K, V> {
+    /// Priority of the entry; smaller keys are popped first.
+    key: K,
+
+    /// Actual value stored in entry.
+    value: V,
+
+    /// Position of the entry in the heap array.
+    pos: usize,
+}
+
+/// A min-priority queue whose entries can be reached through stable handles.
+///
+/// Entries are stored in an [`Arena`], and a handle is the index of an entry in it. Handles stay
+/// valid until the entry is popped or removed, which makes it possible to lower the key of an
+/// entry or remove it from the middle of the queue, as needed by Dijkstra's or Prim's
+/// algorithm. Pushing, popping, lowering a key and removing an entry all take `O(log n)` time.
+///
+/// Entries with the smallest key are popped first. Entries with equal keys are popped in an
+/// unspecified order.
+///
+/// # Examples
+///
+/// ```
+/// use vec_arena::PriorityQueue;
+///
+/// let mut queue = PriorityQueue::new();
+/// let a = queue.push(3, "a");
+/// let b = queue.push(2, "b");
+/// queue.push(1, "c");
+///
+/// queue.decrease_key(a, 0);
+/// assert_eq!(queue.remove(b), Some((2, "b")));
+///
+/// assert_eq!(queue.pop(), Some((0, "a")));
+/// assert_eq!(queue.pop(), Some((1, "c")));
+/// assert_eq!(queue.pop(), None);
+/// ```
+pub struct PriorityQueue<K, V> {
+    /// This is where entries are stored, so that their indices can serve as handles.
+    entries: Arena<Entry<K, V>>,
+
+    /// Binary min-heap of entry handles.
+    heap: Vec<usize>,
+}
+
+impl<K: Ord, V> PriorityQueue<K, V> {
+    /// Constructs a new, empty priority queue.
+    ///
+    /// The queue will not allocate until entries are pushed into it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let queue: PriorityQueue<u32, &str> = PriorityQueue::new();
+    /// assert!(queue.is_empty());
+    /// ```
+    pub const fn new() -> Self {
+        PriorityQueue {
+            entries: Arena::new(),
+            heap: Vec::new(),
+        }
+    }
+
+    /// Constructs a new, empty priority queue with the specified capacity.
+    ///
+    /// The queue will be able to hold exactly `cap` entries without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::with_capacity(10);
+    /// queue.push(1, "a");
+    /// assert_eq!(queue.len(), 1);
+    /// ```
+    pub fn with_capacity(cap: usize) -> Self {
+        PriorityQueue {
+            entries: Arena::with_capacity(cap),
+            heap: Vec::with_capacity(cap),
+        }
+    }
+
+    /// Returns the number of entries in the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// assert_eq!(queue.len(), 0);
+    ///
+    /// queue.push(1, "a");
+    /// assert_eq!(queue.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the queue contains no entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// assert!(queue.is_empty());
+    ///
+    /// queue.push(1, "a");
+    /// assert!(!queue.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Pushes `value` with priority `key` and returns a handle to the new entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// let a = queue.push(2, "a");
+    /// let b = queue.push(1, "b");
+    ///
+    /// assert_eq!(queue.get(a), Some((&2, &"a")));
+    /// assert_eq!(queue.peek(), Some((b, &1, &"b")));
+    /// ```
+    pub fn push(&mut self, key: K, value: V) -> usize {
+        let pos = self.heap.len();
+        let handle = self.entries.insert(Entry { key, value, pos });
+        self.heap.push(handle);
+        self.sift_up(pos);
+        handle
+    }
+
+    /// Returns the handle, key and value of the entry with the smallest key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// assert_eq!(queue.peek(), None);
+    ///
+    /// queue.push(2, "a");
+    /// let b = queue.push(1, "b");
+    /// assert_eq!(queue.peek(), Some((b, &1, &"b")));
+    /// ```
+    pub fn peek(&self) -> Option<(usize, &K, &V)> {
+        let handle = *self.heap.first()?;
+        let entry = &self.entries[handle];
+        Some((handle, &entry.key, &entry.value))
+    }
+
+    /// Removes the entry with the smallest key and returns its key and value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// queue.push(2, "a");
+    /// queue.push(1, "b");
+    ///
+    /// assert_eq!(queue.pop(), Some((1, "b")));
+    /// assert_eq!(queue.pop(), Some((2, "a")));
+    /// assert_eq!(queue.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let handle = *self.heap.first()?;
+        self.remove(handle)
+    }
+
+    /// Returns the key and value of the entry specified by `handle`.
+    ///
+    /// If there is no such entry, [`None`] will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// let a = queue.push(1, "a");
+    /// assert_eq!(queue.get(a), Some((&1, &"a")));
+    ///
+    /// queue.pop();
+    /// assert_eq!(queue.get(a), None);
+    /// ```
+    pub fn get(&self, handle: usize) -> Option<(&K, &V)> {
+        let entry = self.entries.get(handle)?;
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Returns a mutable reference to the value of the entry specified by `handle`.
+    ///
+    /// If there is no such entry, [`None`] will be returned. The key cannot be changed this way;
+    /// use [`decrease_key()`][`PriorityQueue::decrease_key()`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// let a = queue.push(1, "a");
+    ///
+    /// *queue.get_mut(a).unwrap() = "b";
+    /// assert_eq!(queue.pop(), Some((1, "b")));
+    /// ```
+    pub fn get_mut(&mut self, handle: usize) -> Option<&mut V> {
+        Some(&mut self.entries.get_mut(handle)?.value)
+    }
+
+    /// Lowers the key of the entry specified by `handle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such entry or if `key` is greater than the current key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// queue.push(2, "a");
+    /// let b = queue.push(3, "b");
+    ///
+    /// queue.decrease_key(b, 1);
+    /// assert_eq!(queue.pop(), Some((1, "b")));
+    /// ```
+    pub fn decrease_key(&mut self, handle: usize, key: K) {
+        let entry = &mut self.entries[handle];
+        assert!(key <= entry.key, "key must not increase");
+        entry.key = key;
+        let pos = entry.pos;
+        self.sift_up(pos);
+    }
+
+    /// Removes the entry specified by `handle` and returns its key and value.
+    ///
+    /// If there is no such entry, [`None`] will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// let a = queue.push(1, "a");
+    /// queue.push(2, "b");
+    ///
+    /// assert_eq!(queue.remove(a), Some((1, "a")));
+    /// assert_eq!(queue.remove(a), None);
+    /// assert_eq!(queue.pop(), Some((2, "b")));
+    /// ```
+    pub fn remove(&mut self, handle: usize) -> Option<(K, V)> {
+        let pos = self.entries.get(handle)?.pos;
+        let last = self.heap.len() - 1;
+        self.swap(pos, last);
+        self.heap.pop();
+
+        if pos < last {
+            self.sift_down(pos);
+            self.sift_up(pos);
+        }
+
+        let entry = self.entries.remove(handle)?;
+        Some((entry.key, entry.value))
+    }
+
+    /// Removes all entries from the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::PriorityQueue;
+    ///
+    /// let mut queue = PriorityQueue::new();
+    /// queue.push(1, "a");
+    ///
+    /// queue.clear();
+    /// assert!(queue.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.heap.clear();
+    }
+
+    /// Returns `true` if the entry at heap position `a` has a smaller key than the one at `b`.
+    fn less(&self, a: usize, b: usize) -> bool {
+        self.entries[self.heap[a]].key < self.entries[self.heap[b]].key
+    }
+
+    /// Swaps the entries at heap positions `a` and `b`.
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.entries[self.heap[a]].pos = a;
+        self.entries[self.heap[b]].pos = b;
+    }
+
+    /// Moves the entry at heap position `pos` up until its parent has a smaller key.
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if !self.less(pos, parent) {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    /// Moves the entry at heap position `pos` down until its children have larger keys.
+    fn sift_down(&mut self, mut pos: usize) {
+        loop {
+            let mut min = pos;
+            for child in [2 * pos + 1, 2 * pos + 2].iter().copied() {
+                if child < self.heap.len() && self.less(child, min) {
+                    min = child;
+                }
+            }
+            if min == pos {
+                break;
+            }
+            self.swap(pos, min);
+            pos = min;
+        }
+    }
+}
+
+impl<K: Ord, V> Default for PriorityQueue<K, V> {
+    fn default() -> Self {
+        PriorityQueue::new()
+    }
+}
+
+impl<K, V> fmt::Debug for PriorityQueue<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PriorityQueue {{ ... }}")
+    }
+}
@@ -0,0 +1,99 @@
+//! Parallel iterator support, enabled by the `rayon` feature.
+
+use alloc::vec::Vec;
+
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelExtend,
+    ParallelIterator,
+};
+
+use crate::{Arena, Slot};
+
+impl<T: Send> Arena<T> {
+    /// Retains objects for which the closure returns `true`, evaluating it in parallel.
+    ///
+    /// All other objects will be removed from the arena. The closure is called on every occupied
+    /// slot in parallel, and the removals are then applied sequentially in index order, leaving
+    /// the arena in the same state as [`retain()`][`Arena::retain()`] would.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let mut arena: Arena<u64> = (0..1000).collect();
+    /// arena.par_retain(|_, v| *v % 3 == 0);
+    ///
+    /// assert_eq!(arena.len(), 334);
+    /// assert_eq!(arena.get(3), Some(&3));
+    /// assert_eq!(arena.get(4), None);
+    /// ```
+    pub fn par_retain<F>(&mut self, f: F)
+    where
+        F: Fn(usize, &mut T) -> bool + Sync,
+    {
+        let keep: Vec<bool> = self
+            .slots
+            .par_iter_mut()
+            .enumerate()
+            .map(|(index, slot)| match slot {
+                Slot::Vacant(_) => true,
+                Slot::Occupied(object) => f(index, object),
+            })
+            .collect();
+
+        for (index, keep) in keep.into_iter().enumerate() {
+            if !keep {
+                self.remove(index);
+            }
+        }
+    }
+
+    /// Drops the arena, dropping its objects in parallel.
+    ///
+    /// This is useful for large arenas of objects that are expensive to drop, where a sequential
+    /// drop would stall the calling thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vec_arena::Arena;
+    ///
+    /// let arena: Arena<Vec<u8>> = (0..1000).map(|i| vec![0; i]).collect();
+    /// arena.par_drop();
+    /// ```
+    pub fn par_drop(self) {
+        self.slots.into_par_iter().for_each(drop);
+    }
+}
+
+/// Inserts objects produced by a parallel iterator.
+///
+/// Objects are collected in parallel and then inserted in iteration order, so they are assigned
+/// the same indices as a sequential series of [`insert()`][`Arena::insert()`] calls would give
+/// them.
+///
+/// # Examples
+///
+/// ```
+/// use rayon::prelude::*;
+/// use vec_arena::Arena;
+///
+/// let mut arena = Arena::new();
+/// arena.par_extend((0..100).into_par_iter().map(|i| i * 2));
+///
+/// assert_eq!(arena.len(), 100);
+/// assert_eq!(arena[10], 20);
+/// ```
+impl<T: Send> ParallelExtend<T> for Arena<T> {
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = T>,
+    {
+        let objects: Vec<T> = par_iter.into_par_iter().collect();
+        self.reserve(objects.len());
+        for object in objects {
+            self.insert(object);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+#![allow(deprecated)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use vec_arena::Arena;
+
+thread_local! {
+    /// Makes allocations on the current thread fail while set.
+    static FAIL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The system allocator, except that it returns null while [`FAIL`] is set.
+struct FailingAlloc;
+
+unsafe impl GlobalAlloc for FailingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if FAIL.with(Cell::get) {
+            std::ptr::null_mut()
+        } else {
+            System.alloc(layout)
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if FAIL.with(Cell::get) {
+            std::ptr::null_mut()
+        } else {
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+}
+
+#[global_allocator]
+static ALLOC: FailingAlloc = FailingAlloc;
+
+/// Runs `f` with allocation failing on the current thread.
+fn failing<R>(f: impl FnOnce() -> R) -> R {
+    FAIL.with(|fail| fail.set(true));
+    let result = f();
+    FAIL.with(|fail| fail.set(false));
+    result
+}
+
+#[test]
+fn insert_or_err() {
+    let mut arena = Arena::with_capacity(4);
+    let capacity = arena.capacity();
+    for i in 0..capacity {
+        arena.insert(i.to_string());
+    }
+
+    // The arena is full, so inserting has to grow it.
+    let object = "x".to_string();
+    let err = failing(|| arena.insert_or_err(object)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("failed to grow the arena: {}", err.reserve_error())
+    );
+    assert_eq!(err.into_inner(), "x");
+
+    assert_eq!(arena.len(), capacity);
+    assert_eq!(arena.capacity(), capacity);
+    for i in 0..capacity {
+        assert_eq!(arena[i], i.to_string());
+    }
+
+    // Reusing a vacant slot doesn't allocate.
+    arena.remove(1);
+    let object = "y".to_string();
+    let index = failing(|| arena.insert_or_err(object).ok());
+    assert_eq!(index, Some(1));
+
+    // Once allocation works again, the arena grows.
+    assert_eq!(arena.insert_or_err("z".to_string()).ok(), Some(capacity));
+}
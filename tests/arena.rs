@@ -1,3 +1,7 @@
+#![allow(deprecated)]
+
+use std::convert::TryFrom;
+
 use vec_arena::Arena;
 
 #[test]
@@ -8,6 +12,16 @@ fn new() {
     assert_eq!(arena.capacity(), 0);
 }
 
+#[test]
+fn new_const() {
+    static ARENA: std::sync::Mutex<Arena<i32>> = std::sync::Mutex::new(Arena::new());
+
+    let mut arena = ARENA.lock().unwrap();
+    assert!(arena.is_empty());
+    assert_eq!(arena.insert(10), 0);
+    assert_eq!(arena[0], 10);
+}
+
 #[test]
 fn insert() {
     let mut arena = Arena::new();
@@ -121,7 +135,7 @@ fn indexing_vacant() {
     let _ = arena.insert(30);
 
     arena.remove(b);
-    arena[b];
+    let _ = &arena[b];
 }
 
 #[test]
@@ -133,7 +147,7 @@ fn invalid_indexing() {
     arena.insert(20);
     arena.insert(30);
 
-    arena[100];
+    let _ = &arena[100];
 }
 
 #[test]
@@ -230,3 +244,1159 @@ fn from_iter() {
     assert_eq!(it.next(), Some((3, &40)));
     assert_eq!(it.next(), None);
 }
+
+#[test]
+fn fold() {
+    let mut arena = Arena::new();
+    for i in 0..10 {
+        arena.insert(i);
+    }
+    for i in (0..10).step_by(3) {
+        arena.remove(i);
+    }
+
+    let sum = arena.iter().fold(0, |acc, (index, &value)| {
+        assert_eq!(index, value);
+        acc + value
+    });
+    assert_eq!(sum, 1 + 2 + 4 + 5 + 7 + 8);
+
+    arena.iter_mut().for_each(|(_, value)| *value *= 10);
+
+    let mut seen = Vec::new();
+    arena
+        .into_iter()
+        .for_each(|(index, value)| seen.push((index, value)));
+    assert_eq!(seen, [(1, 10), (2, 20), (4, 40), (5, 50), (7, 70), (8, 80)]);
+}
+
+#[test]
+fn swap_remove() {
+    let mut arena = Arena::new();
+    for i in 0..5 {
+        arena.insert(i * 10);
+    }
+
+    assert_eq!(arena.swap_remove(1), Some((10, Some(4))));
+    assert_eq!(arena.len(), 4);
+    assert_eq!(arena[1], 40);
+    assert_eq!(arena.get(4), None);
+
+    assert_eq!(arena.swap_remove(3), Some((30, None)));
+    assert_eq!(arena.swap_remove(3), None);
+    assert_eq!(arena.swap_remove(100), None);
+
+    let mut it = arena.iter();
+    assert_eq!(it.next(), Some((0, &0)));
+    assert_eq!(it.next(), Some((1, &40)));
+    assert_eq!(it.next(), Some((2, &20)));
+    assert_eq!(it.next(), None);
+
+    assert_eq!(arena.insert(50), 3);
+
+    arena.remove(0);
+    assert_eq!(arena.swap_remove(1), Some((40, Some(3))));
+    assert_eq!(arena[1], 50);
+    assert_eq!(arena.insert(60), 0);
+    assert_eq!(arena.insert(70), 3);
+}
+
+#[test]
+fn try_into_vec() {
+    let arena: Arena<_> = (0..5).collect();
+    assert_eq!(Vec::try_from(arena).unwrap(), [0, 1, 2, 3, 4]);
+
+    let mut arena: Arena<_> = (0..5).collect();
+    arena.remove(4);
+    arena.remove(3);
+    assert_eq!(Vec::try_from(arena).unwrap(), [0, 1, 2]);
+
+    let mut arena: Arena<_> = (0..5).collect();
+    arena.remove(3);
+    arena.remove(1);
+    let err = Vec::try_from(arena).unwrap_err();
+    assert_eq!(err.first_vacant(), 1);
+    assert_eq!(err.into_arena().len(), 3);
+
+    let arena = Arena::<i32>::new();
+    assert!(Vec::try_from(arena).unwrap().is_empty());
+}
+
+#[test]
+fn is_dense() {
+    let mut arena = Arena::new();
+    assert!(arena.is_dense());
+
+    for i in 0..5 {
+        arena.insert(i);
+    }
+    assert!(arena.is_dense());
+
+    arena.remove(4);
+    arena.remove(3);
+    assert!(arena.is_dense());
+
+    arena.remove(0);
+    assert!(!arena.is_dense());
+
+    arena.insert(10);
+    assert!(arena.is_dense());
+
+    arena.clear();
+    assert!(arena.is_dense());
+}
+
+#[test]
+fn is_dense_random() {
+    let mut seed = 0x9e37_79b9_u64;
+    let mut rand = move |n: usize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize % n
+    };
+
+    let mut arena = Arena::new();
+    let mut other = Arena::new();
+    for step in 0..5000 {
+        let slots = arena.len() + 8;
+        match rand(9) {
+            0..=2 => {
+                arena.insert(step);
+            }
+            3..=4 => {
+                arena.remove(rand(slots));
+            }
+            5 => {
+                arena.swap_remove(rand(slots));
+            }
+            6 => {
+                arena.transfer(rand(slots), &mut other);
+            }
+            7 => {
+                arena.compact_step(1, |_, _| {});
+            }
+            _ => match rand(3) {
+                0 => arena.retain(|_, v| *v % 5 != 0),
+                1 => arena.shrink_to_fit(),
+                _ => arena.normalize_free_list(),
+            },
+        }
+
+        let dense = arena.iter().all(|(index, _)| index < arena.len());
+        assert_eq!(arena.is_dense(), dense);
+    }
+}
+
+#[test]
+fn occupancy_words() {
+    let bits = std::mem::size_of::<usize>() * 8;
+
+    let arena = Arena::<i32>::new();
+    assert_eq!(arena.occupancy_words().next(), None);
+
+    let mut arena = Arena::new();
+    for i in 0..bits + 3 {
+        arena.insert(i);
+    }
+    for i in (0..bits + 3).filter(|i| i % 2 == 1) {
+        arena.remove(i);
+    }
+
+    let words: Vec<usize> = arena.occupancy_words().collect();
+    assert_eq!(words.len(), 2);
+    for (index, _) in arena.iter() {
+        assert_ne!(words[index / bits] & 1 << (index % bits), 0);
+    }
+    assert_eq!(
+        words[0].count_ones() + words[1].count_ones(),
+        arena.len() as u32
+    );
+    assert_eq!(words[1], 0b101);
+}
+
+#[test]
+fn dump_state() {
+    let arena = Arena::<i32>::new();
+    assert_eq!(
+        arena.dump_state().to_string(),
+        "len: 0, slots: 0, capacity: 0\nvacant list: end\n"
+    );
+
+    let mut arena = Arena::with_capacity(4);
+    for i in 0..3 {
+        arena.insert(i);
+    }
+    arena.remove(0);
+    arena.remove(2);
+    assert_eq!(
+        arena.dump_state().to_string(),
+        "len: 1, slots: 3, capacity: 4\n\
+         0: vacant -> end\n\
+         1: occupied\n\
+         2: vacant -> 0\n\
+         vacant list: 2 -> 0 -> end\n"
+    );
+}
+
+#[test]
+fn iter_sorted_by() {
+    let mut arena = Arena::new();
+    let a = arena.insert("delta");
+    let b = arena.insert("alpha");
+    let c = arena.insert("charlie");
+    let d = arena.insert("bravo");
+    let e = arena.insert("alpha");
+    arena.remove(c);
+
+    let sorted: Vec<_> = arena.iter_sorted_by(|x, y| x.cmp(y)).collect();
+    assert_eq!(
+        sorted,
+        [(b, &"alpha"), (e, &"alpha"), (d, &"bravo"), (a, &"delta")]
+    );
+
+    let mut it = arena.iter_sorted_by(|x, y| y.cmp(x));
+    assert_eq!(it.len(), 4);
+    assert_eq!(it.next_back(), Some((e, &"alpha")));
+    assert_eq!(it.next(), Some((a, &"delta")));
+    assert_eq!(it.len(), 2);
+}
+
+#[test]
+fn filter_map() {
+    let mut arena = Arena::new();
+    for i in 0..6 {
+        arena.insert(i);
+    }
+    arena.remove(1);
+
+    let mut odd = arena.filter_map(|index, value| {
+        assert_eq!(index, value);
+        if value % 2 == 1 {
+            Some(value.to_string())
+        } else {
+            None
+        }
+    });
+    assert_eq!(odd.len(), 2);
+    assert_eq!(odd.get(3), Some(&"3".to_string()));
+    assert_eq!(odd.get(5), Some(&"5".to_string()));
+
+    assert_eq!(odd.insert("a".to_string()), 0);
+    assert_eq!(odd.insert("b".to_string()), 1);
+    assert_eq!(odd.insert("c".to_string()), 2);
+    assert_eq!(odd.insert("d".to_string()), 4);
+    assert_eq!(odd.insert("e".to_string()), 6);
+}
+
+#[test]
+fn partition() {
+    let mut arena = Arena::new();
+    for i in 0..10 {
+        arena.insert(i);
+    }
+    arena.remove(4);
+    arena.remove(7);
+
+    let (small, large) = arena.partition(|index, &value| {
+        assert_eq!(index, value);
+        value < 5
+    });
+    assert_eq!(small.len(), 4);
+    assert_eq!(large.len(), 4);
+
+    for i in 0..10 {
+        match i {
+            4 | 7 => assert!(small.get(i).is_none() && large.get(i).is_none()),
+            0..=3 => assert!(small.get(i) == Some(&i) && large.get(i).is_none()),
+            _ => assert!(small.get(i).is_none() && large.get(i) == Some(&i)),
+        }
+    }
+}
+
+#[test]
+fn content_hash() {
+    let mut a = Arena::new();
+    let mut b = Arena::with_capacity(64);
+    assert_eq!(a.content_hash(), b.content_hash());
+
+    for i in 0..10u32 {
+        a.insert(i);
+        b.insert(i);
+    }
+    assert_eq!(a.content_hash(), b.content_hash());
+
+    // Same values at different indices hash differently.
+    a.remove(3);
+    b.remove(4);
+    assert_ne!(a.content_hash(), b.content_hash());
+
+    // Vacant slots and their order in the vacant list don't matter.
+    a.remove(4);
+    b.remove(3);
+    assert_eq!(a.content_hash(), b.content_hash());
+
+    // The algorithm is fixed, so the hash of a known arena never changes.
+    let arena: Arena<u32> = (1..=3).collect();
+    assert_eq!(arena.content_hash(), 0xb793_6779_983e_b796);
+}
+
+#[test]
+fn extend_from_arena() {
+    let mut template = Arena::new();
+    for i in 0..4 {
+        template.insert(i.to_string());
+    }
+    template.remove(2);
+
+    let mut arena = Arena::new();
+    let x = arena.insert("x".to_string());
+    let y = arena.insert("y".to_string());
+    arena.remove(x);
+
+    let first = arena.extend_from_arena(&template);
+    let second = arena.extend_from_arena(&template);
+    assert_eq!(arena.len(), 7);
+    assert_eq!(arena[y], "y");
+    assert_eq!(first.len(), 4);
+    assert_eq!(first[2], None);
+    assert_eq!(second[2], None);
+
+    for i in [0, 1, 3].iter().copied() {
+        assert_eq!(arena[first[i].unwrap()], i.to_string());
+        assert_eq!(arena[second[i].unwrap()], i.to_string());
+        assert_ne!(first[i], second[i]);
+    }
+    assert_eq!(first[0], Some(x));
+    assert_eq!(template.len(), 3);
+}
+
+#[test]
+fn compact_step() {
+    let mut arena = Arena::new();
+    for i in 0..10 {
+        arena.insert(i);
+    }
+    for i in [1, 3, 4, 8].iter() {
+        arena.remove(*i);
+    }
+
+    let mut moves = Vec::new();
+    assert!(!arena.compact_step(0, |from, to| moves.push((from, to))));
+    assert!(moves.is_empty());
+
+    assert!(!arena.compact_step(2, |from, to| moves.push((from, to))));
+    assert!(arena.compact_step(usize::MAX, |from, to| moves.push((from, to))));
+    assert_eq!(moves, [(9, 1), (7, 3), (6, 4)]);
+    assert!(arena.is_dense());
+    assert_eq!(arena.len(), 6);
+
+    let values: Vec<_> = arena.iter().map(|(_, &v)| v).collect();
+    assert_eq!(values, [0, 9, 2, 7, 6, 5]);
+
+    for i in 6..10 {
+        assert_eq!(arena.insert(i * 10), i);
+    }
+    assert_eq!(arena.insert(100), 10);
+}
+
+#[test]
+fn ranges() {
+    let arena = Arena::<i32>::new();
+    assert_eq!(arena.occupied_ranges().next(), None);
+    assert_eq!(arena.vacant_ranges().next(), None);
+
+    let mut arena: Arena<_> = (0..10).collect();
+    let mut runs = arena.occupied_ranges();
+    assert_eq!(runs.next(), Some(0..10));
+    assert_eq!(runs.next(), None);
+    assert_eq!(arena.vacant_ranges().next(), None);
+
+    for i in [0, 4, 5, 6, 9].iter() {
+        arena.remove(*i);
+    }
+    assert_eq!(arena.occupied_ranges().collect::<Vec<_>>(), [1..4, 7..9]);
+    assert_eq!(
+        arena.vacant_ranges().collect::<Vec<_>>(),
+        [0..1, 4..7, 9..10]
+    );
+
+    arena.clear();
+    assert_eq!(arena.occupied_ranges().next(), None);
+}
+
+#[test]
+fn try_insert_with() {
+    let mut arena = Arena::new();
+    let a = arena.insert((0, 0));
+    arena.insert((1, 1));
+    arena.remove(a);
+
+    assert_eq!(
+        arena.try_insert_with(|_| Err::<(usize, i32), _>(())),
+        Err(())
+    );
+    assert_eq!(arena.len(), 1);
+    assert_eq!(arena.next_vacant(), a);
+
+    assert_eq!(arena.try_insert_with(|i| Ok::<_, ()>((i, 10))), Ok(a));
+    assert_eq!(arena[a], (a, 10));
+    assert_eq!(arena.try_insert_with(|i| Ok::<_, ()>((i, 20))), Ok(2));
+    assert_eq!(arena[2], (2, 20));
+}
+
+#[test]
+fn try_reserve() {
+    let mut arena = Arena::new();
+    arena.insert(1);
+    arena.insert(2);
+
+    arena.try_reserve(10).unwrap();
+    assert!(arena.capacity() >= 12);
+
+    let cap = arena.capacity();
+    assert!(arena.try_reserve(usize::MAX).is_err());
+    assert_eq!(arena.capacity(), cap);
+    assert_eq!(arena.len(), 2);
+}
+
+#[test]
+fn insert_or_err() {
+    let mut arena = Arena::new();
+    for i in 0..10 {
+        assert_eq!(arena.insert_or_err(i * 10).unwrap(), i);
+    }
+    arena.remove(3);
+    assert_eq!(arena.insert_or_err(1).unwrap(), 3);
+    assert_eq!(arena.len(), 10);
+}
+
+#[test]
+fn iter_from() {
+    let mut arena: Arena<_> = (0..10).collect();
+    arena.remove(5);
+
+    let rest: Vec<_> = arena.iter_from(4).map(|(i, _)| i).collect();
+    assert_eq!(rest, [4, 6, 7, 8, 9]);
+    assert_eq!(arena.iter_from(9).next(), Some((9, &9)));
+    assert_eq!(arena.iter_from(10).next(), None);
+    assert_eq!(arena.iter_from(!0).next(), None);
+
+    // Paging through while removing and inserting never repeats or skips survivors.
+    let mut seen = Vec::new();
+    let mut cursor = 0;
+    loop {
+        let page: Vec<_> = arena.iter_from(cursor).take(3).map(|(i, _)| i).collect();
+        match page.last() {
+            None => break,
+            Some(&last) => cursor = last + 1,
+        }
+        seen.extend(page);
+
+        arena.remove(0);
+        arena.insert(100);
+    }
+    assert_eq!(seen, [0, 1, 2, 3, 4, 6, 7, 8, 9]);
+}
+
+#[test]
+fn apply_permutation() {
+    let mut arena: Arena<_> = (0..6).collect();
+    arena.remove(1);
+    arena.remove(4);
+
+    arena.apply_permutation(&[5, 3, 0, 2]);
+    assert!(arena.is_dense());
+    assert_eq!(arena.len(), 4);
+    let values: Vec<_> = arena.iter().map(|(i, &v)| (i, v)).collect();
+    assert_eq!(values, [(0, 5), (1, 3), (2, 0), (3, 2)]);
+    assert_eq!(arena.insert(10), 4);
+
+    let mut empty = Arena::<i32>::new();
+    empty.apply_permutation(&[]);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn apply_permutation_invalid() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena: Arena<_> = (0..3).collect();
+    arena.remove(1);
+
+    for order in [&[0, 1][..], &[0, 5], &[0], &[0, 2, 2]].iter() {
+        let result = catch_unwind(AssertUnwindSafe(|| arena.apply_permutation(order)));
+        assert!(result.is_err());
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena.get(0), Some(&0));
+        assert_eq!(arena.get(2), Some(&2));
+    }
+}
+
+#[test]
+fn insert_and_get_mut() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    arena.insert(2);
+    arena.remove(a);
+
+    let (index, object) = arena.insert_and_get_mut(3);
+    assert_eq!(index, a);
+    *object += 10;
+    assert_eq!(arena[a], 13);
+
+    let (index, object) = arena.insert_and_get_mut(4);
+    assert_eq!((index, *object), (2, 4));
+}
+
+#[test]
+fn dedup_all() {
+    let mut arena = Arena::new();
+    for &v in [3, 1, 3, 2, 1, 3, 4].iter() {
+        arena.insert(v);
+    }
+    arena.remove(0);
+
+    assert_eq!(arena.dedup_all(), 2);
+    let values: Vec<_> = arena.iter().map(|(i, &v)| (i, v)).collect();
+    assert_eq!(values, [(1, 1), (2, 3), (3, 2), (6, 4)]);
+    assert_eq!(arena.dedup_all(), 0);
+}
+
+#[test]
+fn dedup_all_by_key() {
+    let mut arena = Arena::new();
+    for v in 0..20 {
+        arena.insert(v);
+    }
+
+    assert_eq!(arena.dedup_all_by_key(|v| v % 7), 13);
+    let values: Vec<_> = arena.iter().map(|(i, &v)| (i, v)).collect();
+    assert_eq!(values, (0..7).map(|v| (v, v)).collect::<Vec<_>>());
+}
+
+#[test]
+fn dedup_all_by() {
+    let mut arena = Arena::new();
+    for &v in [3.0, 1.0, 3.0, 2.0, 1.0, 3.0, 4.0].iter() {
+        arena.insert(v);
+    }
+    arena.remove(0);
+
+    assert_eq!(arena.dedup_all_by(|a, b| a == b), 2);
+    let values: Vec<_> = arena.iter().map(|(i, &v)| (i, v)).collect();
+    assert_eq!(values, [(1, 1.0), (2, 3.0), (3, 2.0), (6, 4.0)]);
+    assert_eq!(arena.dedup_all_by(|a, b| a == b), 0);
+
+    // The object at the lower index is passed first.
+    let mut seen = Vec::new();
+    arena.dedup_all_by(|a, b| {
+        seen.push((*a, *b));
+        false
+    });
+    assert_eq!(seen.len(), 6);
+    assert!(seen.iter().all(|&(a, b)| a != b));
+    assert_eq!(seen[0], (1.0, 3.0));
+}
+
+#[test]
+fn normalize_free_list() {
+    let mut arena: Arena<_> = (0..10).collect();
+    for i in [7, 2, 9, 0, 4].iter() {
+        arena.remove(*i);
+    }
+
+    arena.normalize_free_list();
+    assert_eq!(arena.len(), 5);
+    for &i in [0, 2, 4, 7, 9].iter() {
+        assert_eq!(arena.insert(i * 10), i);
+    }
+    assert_eq!(arena.insert(100), 10);
+
+    let mut empty = Arena::<i32>::new();
+    empty.normalize_free_list();
+    assert_eq!(empty.insert(1), 0);
+}
+
+#[test]
+fn iter_masked() {
+    let bits = std::mem::size_of::<usize>() * 8;
+
+    let mut arena: Arena<_> = (0..bits * 2 + 5).collect();
+    for i in (0..arena.len()).step_by(5) {
+        arena.remove(i);
+    }
+
+    let mut mask = vec![0usize; 4];
+    let wanted = [0, 1, 2, 5, 6, bits - 1, bits, bits * 2 + 4, bits * 3 + 1];
+    for &i in wanted.iter() {
+        mask[i / bits] |= 1 << (i % bits);
+    }
+
+    let expected: Vec<usize> = wanted
+        .iter()
+        .copied()
+        .filter(|&i| arena.get(i).is_some())
+        .collect();
+    let selected: Vec<usize> = arena
+        .iter_masked(&mask)
+        .map(|(i, &v)| {
+            assert_eq!(i, v);
+            i
+        })
+        .collect();
+    assert_eq!(selected, expected);
+
+    for (_, value) in arena.iter_masked_mut(&mask) {
+        *value += 1000;
+    }
+    for (index, &value) in arena.iter() {
+        if expected.contains(&index) {
+            assert_eq!(value, index + 1000);
+        } else {
+            assert_eq!(value, index);
+        }
+    }
+
+    assert_eq!(arena.iter_masked(&[]).next(), None);
+    assert_eq!(arena.iter_masked_mut(&[0, 0]).next(), None);
+}
+
+#[test]
+fn try_clone() {
+    let mut arena: Arena<_> = (0..10).map(|i| i.to_string()).collect();
+    arena.remove(7);
+    arena.remove(2);
+
+    let mut copy = arena.try_clone().unwrap();
+    assert_eq!(copy.len(), arena.len());
+    assert!(copy.iter().eq(arena.iter()));
+    assert_eq!(copy.insert("x".into()), 2);
+    assert_eq!(copy.insert("y".into()), 7);
+
+    let empty: Arena<String> = Arena::new();
+    assert!(empty.try_clone().unwrap().is_empty());
+}
+
+#[test]
+fn null_index() {
+    let mut arena = Arena::new();
+    arena.insert(());
+    let a = arena.insert(());
+    arena.remove(a);
+
+    assert_eq!(arena.get(!0), None);
+    assert_eq!(arena.get_mut(!0), None);
+    assert_eq!(arena.remove(!0), None);
+    assert_eq!(arena.next_vacant(), a);
+}
+
+#[test]
+fn insert_within_capacity() {
+    let mut arena = Arena::new();
+    assert_eq!(arena.insert_within_capacity(0), Err(0));
+    assert_eq!(arena.capacity(), 0);
+
+    arena.reserve_exact(3);
+    let capacity = arena.capacity();
+    for i in 0..capacity {
+        assert_eq!(arena.insert_within_capacity(i), Ok(i));
+    }
+    assert_eq!(arena.insert_within_capacity(99), Err(99));
+    assert_eq!(arena.capacity(), capacity);
+
+    arena.remove(1);
+    assert_eq!(arena.insert_within_capacity(10), Ok(1));
+    assert_eq!(arena.insert_within_capacity(11), Err(11));
+    assert_eq!(arena.capacity(), capacity);
+}
+
+#[test]
+fn fixed() {
+    let mut arena = Arena::with_capacity(4);
+    arena.insert(0);
+
+    let mut fixed = arena.fixed();
+    let capacity = fixed.capacity();
+    for i in 1..capacity {
+        assert_eq!(fixed.insert(i), Ok(i));
+    }
+    assert_eq!(fixed.insert(99), Err(99));
+    assert_eq!(fixed.len(), capacity);
+
+    assert_eq!(fixed.remove(2), Some(2));
+    assert_eq!(fixed.get(2), None);
+    *fixed.get_mut(1).unwrap() = 10;
+    for (_, v) in fixed.iter_mut() {
+        *v += 1;
+    }
+    assert_eq!(fixed.insert(20), Ok(2));
+    assert_eq!(
+        fixed.iter().map(|(_, &v)| v).sum::<usize>(),
+        20 + 11 + 1 + 4
+    );
+
+    assert_eq!(arena.capacity(), capacity);
+    assert_eq!(arena.len(), capacity);
+}
+
+#[test]
+fn transfer() {
+    let mut a: Arena<_> = (0..5).collect();
+    let mut b = Arena::new();
+    let x = b.insert(100);
+    b.insert(101);
+    b.remove(x);
+
+    assert_eq!(a.transfer(3, &mut b), Some(x));
+    assert_eq!(a.transfer(1, &mut b), Some(2));
+    let capacity = b.capacity();
+    assert_eq!(a.transfer(3, &mut b), None);
+    assert_eq!(a.transfer(10, &mut b), None);
+    assert_eq!(b.capacity(), capacity);
+
+    assert_eq!(a.len(), 3);
+    assert_eq!(b.len(), 3);
+    assert_eq!(b[x], 3);
+    assert_eq!(b[2], 1);
+
+    // Both free lists are updated.
+    assert_eq!(a.insert(10), 1);
+    assert_eq!(a.insert(11), 3);
+    assert_eq!(b.insert(102), 3);
+}
+
+#[test]
+fn insert_tentative() {
+    fn register(arena: &mut Arena<String>, name: &str) -> Result<usize, ()> {
+        let mut entry = arena.insert_tentative(String::new());
+        entry.push_str(name);
+        if name.is_empty() {
+            return Err(());
+        }
+        Ok(entry.commit())
+    }
+
+    let mut arena = Arena::new();
+    assert_eq!(register(&mut arena, "a"), Ok(0));
+    assert_eq!(register(&mut arena, ""), Err(()));
+    assert_eq!(arena.len(), 1);
+    assert_eq!(arena.get(1), None);
+    assert_eq!(register(&mut arena, "b"), Ok(1));
+
+    arena.remove(0);
+    let entry = arena.insert_tentative("c".to_string());
+    assert_eq!(entry.index(), 0);
+    assert_eq!(*entry, "c");
+    drop(entry);
+    assert_eq!(arena.len(), 1);
+    assert_eq!(arena.insert("d".to_string()), 0);
+}
+
+#[test]
+fn gather() {
+    let mut arena: Arena<_> = (0..10).collect();
+    arena.remove(4);
+
+    let indices = [9, 4, 0, 100, 9, !0, 3];
+    assert_eq!(
+        arena.gather(&indices),
+        [Some(&9), None, Some(&0), None, Some(&9), None, Some(&3)]
+    );
+    assert!(arena.gather(&[]).is_empty());
+}
+
+#[test]
+fn scatter() {
+    let mut arena: Arena<_> = (0..5).map(|i| i.to_string()).collect();
+    arena.remove(2);
+
+    let updates = vec![(0, "a"), (2, "b"), (4, "c"), (7, "d"), (0, "e")];
+    let rejected = arena.scatter(updates.into_iter().map(|(i, s)| (i, s.to_string())));
+
+    assert_eq!(rejected, [(2, "b".to_string()), (7, "d".to_string())]);
+    assert_eq!(arena[0], "e");
+    assert_eq!(arena[1], "1");
+    assert_eq!(arena[4], "c");
+    assert_eq!(arena.get(2), None);
+    assert_eq!(arena.len(), 4);
+}
+
+#[test]
+fn retain() {
+    let mut arena: Arena<_> = (0..10).collect();
+    arena.remove(5);
+
+    let mut seen = Vec::new();
+    arena.retain(|index, value| {
+        seen.push(index);
+        *value *= 10;
+        index % 3 != 0
+    });
+
+    assert_eq!(seen, [0, 1, 2, 3, 4, 6, 7, 8, 9]);
+    assert_eq!(arena.len(), 5);
+    assert_eq!(
+        arena.iter().collect::<Vec<_>>(),
+        [(1, &10), (2, &20), (4, &40), (7, &70), (8, &80)]
+    );
+
+    // Removed slots are reused, most recently removed first.
+    assert_eq!(arena.insert(0), 9);
+    assert_eq!(arena.insert(0), 6);
+    assert_eq!(arena.insert(0), 3);
+    assert_eq!(arena.insert(0), 0);
+    assert_eq!(arena.insert(0), 5);
+}
+
+#[test]
+fn drain() {
+    let mut arena: Arena<_> = (0..10).map(|i| i.to_string()).collect();
+    arena.remove(3);
+    arena.remove(8);
+    let capacity = arena.capacity();
+
+    let drained: Vec<_> = arena.drain().collect();
+    assert_eq!(drained.len(), 8);
+    assert_eq!(drained[3], (4, "4".to_string()));
+    assert!(arena.is_empty());
+    assert_eq!(arena.capacity(), capacity);
+    assert_eq!(arena.insert("a".to_string()), 0);
+
+    // Objects not yielded are dropped, and the arena is still emptied.
+    arena.insert("b".to_string());
+    let mut iter = arena.drain();
+    assert_eq!(iter.next(), Some((0, "a".to_string())));
+    drop(iter);
+    assert!(arena.is_empty());
+    assert_eq!(arena.insert("c".to_string()), 0);
+}
+
+#[test]
+fn extract_if() {
+    let mut arena: Arena<_> = (0..10).collect();
+    arena.remove(6);
+
+    let mut visited = Vec::new();
+    {
+        let mut iter = arena.extract_if(|index, value| {
+            visited.push(index);
+            *value += 100;
+            index % 3 == 0
+        });
+        assert_eq!(iter.next(), Some((0, 100)));
+        assert_eq!(iter.next(), Some((3, 103)));
+    }
+
+    // Slots past the last yielded one were not visited.
+    assert_eq!(visited, [0, 1, 2, 3]);
+    assert_eq!(arena.len(), 7);
+    assert_eq!(arena[1], 101);
+    assert_eq!(arena[9], 9);
+
+    let rest: Vec<_> = arena.extract_if(|_, value| *value < 100).collect();
+    assert_eq!(rest, [(4, 4), (5, 5), (7, 7), (8, 8), (9, 9)]);
+    assert_eq!(arena.iter().collect::<Vec<_>>(), [(1, &101), (2, &102)]);
+    assert_eq!(arena.insert(0), 9);
+}
+
+#[test]
+fn vacant_entry() {
+    let mut arena = Arena::new();
+    for i in 0..5 {
+        let entry = arena.vacant_entry();
+        assert_eq!(entry.index(), i);
+        entry.insert(i);
+    }
+
+    arena.remove(3);
+    arena.remove(1);
+
+    let entry = arena.vacant_entry();
+    let index = entry.index();
+    assert_eq!(index, 1);
+    *entry.insert(index * 10) += 1;
+    assert_eq!(arena[1], 11);
+
+    // Dropping an entry leaves the arena unchanged.
+    let len = arena.len();
+    assert_eq!(arena.vacant_entry().index(), 3);
+    assert_eq!(arena.len(), len);
+    assert_eq!(arena.insert(30), 3);
+}
+
+#[test]
+fn insert_with() {
+    let mut arena = Arena::new();
+    for i in 0..4 {
+        assert_eq!(arena.insert_with(|index| index * 10), i);
+    }
+
+    arena.remove(2);
+    arena.remove(0);
+    assert_eq!(arena.insert_with(|index| index * 10 + 1), 0);
+    assert_eq!(arena.insert_with(|index| index * 10 + 1), 2);
+    assert_eq!(arena.insert_with(|index| index * 10 + 1), 4);
+    assert_eq!(
+        arena.iter().map(|(_, &v)| v).collect::<Vec<_>>(),
+        [1, 10, 21, 30, 41]
+    );
+}
+
+#[test]
+fn clone_subset() {
+    let mut arena: Arena<_> = (0..10).map(|i| i.to_string()).collect();
+    arena.remove(5);
+
+    let mut subset = arena.clone_subset(vec![8, 2, 5, 2, 100]);
+    assert_eq!(subset.len(), 2);
+    assert!(subset.capacity() >= 9);
+    assert_eq!(
+        subset.iter().collect::<Vec<_>>(),
+        [(2, &"2".to_string()), (8, &"8".to_string())]
+    );
+    assert_eq!(subset.insert("x".into()), 0);
+    assert_eq!(subset.insert("y".into()), 1);
+    assert_eq!(subset.insert("z".into()), 3);
+
+    assert!(arena.clone_subset(None).is_empty());
+    assert_eq!(arena.len(), 9);
+}
+
+#[test]
+fn get2_mut() {
+    let mut arena: Arena<_> = (0..5).collect();
+    arena.remove(2);
+
+    let (a, b) = arena.get2_mut(4, 1);
+    std::mem::swap(a.unwrap(), b.unwrap());
+    assert_eq!(arena[1], 4);
+    assert_eq!(arena[4], 1);
+
+    assert_eq!(arena.get2_mut(0, 2), (Some(&mut 0), None));
+    assert_eq!(arena.get2_mut(2, 0), (None, Some(&mut 0)));
+    assert_eq!(arena.get2_mut(9, 3), (None, Some(&mut 3)));
+    assert_eq!(arena.get2_mut(8, 9), (None, None));
+}
+
+#[test]
+#[should_panic]
+fn get2_mut_same_index() {
+    let mut arena = Arena::new();
+    let a = arena.insert(0);
+    let _ = arena.get2_mut(a, a);
+}
+
+#[test]
+fn get_many_mut() {
+    let mut arena: Arena<_> = (0..8).collect();
+    arena.remove(3);
+
+    let [a, b, c, d] = arena.get_many_mut([7, 0, 5, 1]).unwrap();
+    assert_eq!((*a, *b, *c, *d), (7, 0, 5, 1));
+    *a = 70;
+    *d = 10;
+    assert_eq!(arena[7], 70);
+    assert_eq!(arena[1], 10);
+
+    assert!(arena.get_many_mut([2, 3]).is_none());
+    assert!(arena.get_many_mut([0, 2, 0]).is_none());
+    assert!(arena.get_many_mut([8]).is_none());
+    assert!(arena.get_many_mut([!0, 0]).is_none());
+    assert_eq!(arena.get_many_mut([]), Some([]));
+    assert_eq!(arena.get_many_mut([6]), Some([&mut 6]));
+}
+
+#[test]
+fn split_index_mut() {
+    let mut arena: Arena<_> = (0..6).collect();
+    arena.remove(4);
+
+    let (object, rest) = arena.split_index_mut(2).unwrap();
+    assert_eq!(*object, 2);
+    assert_eq!(rest.len(), 4);
+    assert!(!rest.is_empty());
+    assert_eq!(rest.get(1), Some(&1));
+    assert_eq!(rest.get(2), None);
+    assert_eq!(rest.get(3), Some(&3));
+    assert_eq!(rest.get(4), None);
+    assert_eq!(rest.get(5), Some(&5));
+    assert_eq!(rest.get(6), None);
+    assert_eq!(
+        rest.iter().collect::<Vec<_>>(),
+        [(0, &0), (1, &1), (3, &3), (5, &5)]
+    );
+    *object = rest.iter().map(|(_, &v)| v).sum();
+    assert_eq!(arena[2], 9);
+
+    let (_, rest) = arena.split_index_mut(0).unwrap();
+    assert_eq!(rest.get(0), None);
+    assert_eq!(rest.iter().next(), Some((1, &1)));
+    let (_, rest) = arena.split_index_mut(5).unwrap();
+    assert_eq!(rest.iter().last(), Some((3, &3)));
+
+    assert!(arena.split_index_mut(4).is_none());
+    assert!(arena.split_index_mut(6).is_none());
+
+    let mut single = Arena::new();
+    single.insert(());
+    assert!(single.split_index_mut(0).unwrap().1.is_empty());
+}
+
+#[test]
+fn shrink_to_fit() {
+    let mut arena: Arena<_> = (0..1000).collect();
+    for i in 10..1000 {
+        arena.remove(i);
+    }
+    arena.remove(3);
+    arena.remove(7);
+
+    arena.shrink_to_fit();
+    assert_eq!(arena.len(), 8);
+    assert!(arena.capacity() >= 10);
+    assert!(arena.capacity() < 1000);
+
+    assert_eq!(arena.insert(30), 3);
+    assert_eq!(arena.insert(70), 7);
+    assert_eq!(arena.insert(100), 10);
+
+    arena.clear();
+    arena.shrink_to_fit();
+    assert_eq!(arena.capacity(), 0);
+}
+
+#[test]
+fn compact() {
+    let mut arena: Arena<_> = (0..20).collect();
+    for i in (0..20).filter(|i| i % 3 != 1) {
+        arena.remove(i);
+    }
+
+    let mut remap = std::collections::HashMap::new();
+    arena.compact(|old, new| {
+        assert!(remap.insert(old, new).is_none());
+    });
+
+    assert_eq!(arena.len(), 7);
+    assert!(arena.is_dense());
+    for (index, &value) in arena.iter() {
+        assert_eq!(remap.get(&value).copied().unwrap_or(value), index);
+    }
+    assert_eq!(arena.next_vacant(), 7);
+
+    let mut empty: Arena<i32> = (0..3).collect();
+    empty.clear();
+    empty.compact(|_, _| unreachable!());
+    assert_eq!(empty.insert(0), 0);
+}
+
+#[test]
+fn split_off() {
+    let mut arena: Arena<_> = (0..10).collect();
+    arena.remove(2);
+    arena.remove(7);
+    arena.remove(5);
+
+    let mut tail = arena.split_off(5);
+    assert_eq!(arena.len(), 4);
+    assert_eq!(tail.len(), 3);
+    assert_eq!(
+        arena.iter().collect::<Vec<_>>(),
+        [(0, &0), (1, &1), (3, &3), (4, &4)]
+    );
+    assert_eq!(tail.iter().collect::<Vec<_>>(), [(6, &6), (8, &8), (9, &9)]);
+
+    assert_eq!(arena.insert(20), 2);
+    assert_eq!(arena.insert(50), 5);
+    assert_eq!(tail.insert(0), 0);
+
+    assert!(arena.split_off(6).is_empty());
+    assert!(arena.split_off(100).is_empty());
+    assert_eq!(arena.len(), 6);
+
+    let all = arena.split_off(0);
+    assert!(arena.is_empty());
+    assert_eq!(all.len(), 6);
+}
+
+#[test]
+fn extend_returning() {
+    let mut arena: Arena<_> = (0..6).collect();
+    arena.remove(1);
+    arena.remove(4);
+
+    let indices = arena.extend_returning((10..14).filter(|_| true));
+    assert_eq!(indices, [4, 1, 6, 7]);
+    for (i, &index) in indices.iter().enumerate() {
+        assert_eq!(arena[index], 10 + i);
+    }
+    assert_eq!(arena.len(), 8);
+
+    assert!(arena.extend_returning(None).is_empty());
+    assert_eq!(arena.len(), 8);
+}
+
+#[test]
+fn compact_step_interleaved() {
+    let mut seed = 0x2545_f491_u64;
+    let mut rand = move |n: usize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize % n
+    };
+
+    for _ in 0..50 {
+        // Objects are their own original index, tracked through `location`.
+        let mut arena: Arena<usize> = Arena::new();
+        let mut location = std::collections::BTreeMap::new();
+        for _ in 0..200 {
+            let id = location.len();
+            location.insert(id, arena.insert(id));
+        }
+        for _ in 0..rand(150) {
+            let id = rand(200);
+            if let Some(index) = location.remove(&id) {
+                assert_eq!(arena.remove(index), Some(id));
+            }
+        }
+
+        let mut next_id = 1000;
+        loop {
+            let mut moved = std::collections::BTreeSet::new();
+            let done = arena.compact_step(1 + rand(5), |from, to| {
+                assert!(from >= to);
+                assert!(moved.insert(to));
+                let id = arena_value(&location, from);
+                location.insert(id, to);
+            });
+
+            for (&id, &index) in &location {
+                assert_eq!(arena[index], id);
+            }
+            if done {
+                break;
+            }
+
+            // Modify the arena between steps.
+            if rand(3) == 0 {
+                next_id += 1;
+                location.insert(next_id, arena.insert(next_id));
+            }
+            if !location.is_empty() && rand(3) == 0 {
+                let id = *location.keys().nth(rand(location.len())).unwrap();
+                let index = location.remove(&id).unwrap();
+                assert_eq!(arena.remove(index), Some(id));
+            }
+        }
+
+        assert!(arena.is_dense());
+        assert_eq!(arena.next_vacant(), arena.len());
+        assert_eq!(arena.len(), location.len());
+    }
+
+    fn arena_value(location: &std::collections::BTreeMap<usize, usize>, index: usize) -> usize {
+        *location.iter().find(|&(_, &i)| i == index).unwrap().0
+    }
+}
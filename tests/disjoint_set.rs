@@ -0,0 +1,95 @@
+#![allow(deprecated)]
+
+use vec_arena::{Arena, DisjointSet};
+
+#[test]
+fn new() {
+    let sets = DisjointSet::new();
+    assert!(sets.is_empty());
+    assert_eq!(sets.len(), 0);
+    assert!(!sets.contains(0));
+}
+
+#[test]
+fn make_set() {
+    let mut sets = DisjointSet::new();
+    for i in 0..10 {
+        assert_eq!(sets.make_set(), i);
+        assert_eq!(sets.find(i), i);
+    }
+    assert_eq!(sets.len(), 10);
+    for i in 0..10 {
+        for j in 0..10 {
+            assert_eq!(sets.same_set(i, j), i == j);
+        }
+    }
+}
+
+#[test]
+fn find_compresses_paths() {
+    let mut sets = DisjointSet::new();
+    let ids: Vec<usize> = (0..64).map(|_| sets.make_set()).collect();
+
+    // Merge pairwise so that trees grow deeper than one level.
+    let mut step = 1;
+    while step < ids.len() {
+        for i in (0..ids.len()).step_by(2 * step) {
+            sets.union(ids[i], ids[i + step]);
+        }
+        step *= 2;
+    }
+
+    let root = sets.find(ids[63]);
+    for &id in &ids {
+        assert_eq!(sets.find(id), root);
+    }
+}
+
+#[test]
+fn union() {
+    let mut sets = DisjointSet::new();
+    let ids: Vec<usize> = (0..6).map(|_| sets.make_set()).collect();
+
+    let root = sets.union(ids[0], ids[1]);
+    assert!(root == ids[0] || root == ids[1]);
+    assert_eq!(sets.union(ids[1], ids[0]), root);
+
+    sets.union(ids[2], ids[3]);
+    assert!(!sets.same_set(ids[0], ids[2]));
+
+    let root = sets.union(ids[1], ids[3]);
+    for &id in &ids[..4] {
+        assert_eq!(sets.find(id), root);
+    }
+    assert!(!sets.same_set(ids[0], ids[4]));
+    assert!(!sets.same_set(ids[4], ids[5]));
+}
+
+#[test]
+fn for_arena() {
+    let mut arena = Arena::new();
+    let ids: Vec<usize> = (0..8).map(|i| arena.insert(i)).collect();
+    arena.remove(ids[2]);
+    arena.remove(ids[5]);
+
+    let mut sets = DisjointSet::for_arena(&arena);
+    assert_eq!(sets.len(), arena.len());
+    for &id in &ids {
+        assert_eq!(sets.contains(id), arena.get(id).is_some());
+    }
+
+    sets.union(ids[0], ids[7]);
+    assert!(sets.same_set(ids[0], ids[7]));
+
+    for _ in 0..4 {
+        assert_eq!(sets.make_set(), arena.insert(0));
+    }
+}
+
+#[test]
+#[should_panic]
+fn find_missing() {
+    let mut sets = DisjointSet::new();
+    sets.make_set();
+    sets.find(1);
+}
@@ -0,0 +1,97 @@
+#![allow(deprecated)]
+
+use vec_arena::PriorityQueue;
+
+#[test]
+fn new() {
+    let queue: PriorityQueue<i32, ()> = PriorityQueue::new();
+    assert!(queue.is_empty());
+    assert_eq!(queue.len(), 0);
+    assert_eq!(queue.peek(), None);
+}
+
+#[test]
+fn push_pop() {
+    let mut queue = PriorityQueue::new();
+    let keys = [5, 3, 8, 1, 9, 2, 7, 3];
+    for (i, &key) in keys.iter().enumerate() {
+        queue.push(key, i);
+    }
+    assert_eq!(queue.len(), keys.len());
+
+    let mut popped = Vec::new();
+    while let Some((key, value)) = queue.pop() {
+        assert_eq!(keys[value], key);
+        popped.push(key);
+    }
+
+    let mut sorted = keys.to_vec();
+    sorted.sort_unstable();
+    assert_eq!(popped, sorted);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn handles() {
+    let mut queue = PriorityQueue::new();
+    let handles: Vec<usize> = (0..100).map(|i| queue.push(1000 + i, i)).collect();
+
+    // Lower every third key and remove every fifth entry.
+    for (i, &handle) in handles.iter().enumerate() {
+        if i % 3 == 0 {
+            queue.decrease_key(handle, 1000 - i);
+        }
+    }
+    for (i, &handle) in handles.iter().enumerate() {
+        if i % 5 == 0 {
+            let (key, value) = queue.remove(handle).unwrap();
+            assert_eq!(value, i);
+            assert_eq!(key, if i % 3 == 0 { 1000 - i } else { 1000 + i });
+            assert_eq!(queue.get(handle), None);
+            assert_eq!(queue.remove(handle), None);
+        }
+    }
+    assert_eq!(queue.len(), 80);
+
+    *queue.get_mut(handles[1]).unwrap() = 1001;
+    assert_eq!(queue.get(handles[1]), Some((&1001, &1001)));
+
+    let mut expected: Vec<(usize, usize)> = (0..100)
+        .filter(|i| i % 5 != 0)
+        .map(|i| {
+            let key = if i % 3 == 0 { 1000 - i } else { 1000 + i };
+            (key, if i == 1 { 1001 } else { i })
+        })
+        .collect();
+    expected.sort_unstable();
+
+    let mut popped = Vec::new();
+    while let Some(entry) = queue.pop() {
+        popped.push(entry);
+    }
+    assert_eq!(popped, expected);
+}
+
+#[test]
+fn reuse_handles() {
+    let mut queue = PriorityQueue::new();
+    let a = queue.push(1, "a");
+    queue.push(2, "b");
+    assert_eq!(queue.pop(), Some((1, "a")));
+
+    let c = queue.push(0, "c");
+    assert_eq!(c, a);
+    assert_eq!(queue.peek(), Some((c, &0, &"c")));
+
+    queue.clear();
+    assert!(queue.is_empty());
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+#[should_panic]
+fn increase_key() {
+    let mut queue = PriorityQueue::new();
+    let a = queue.push(1, ());
+    queue.decrease_key(a, 2);
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "rayon")]
+#![allow(deprecated)]
+
+use rayon::prelude::*;
+use vec_arena::Arena;
+
+#[test]
+fn par_extend() {
+    let mut arena = Arena::new();
+    let a = arena.insert(-1);
+    let b = arena.insert(-2);
+    arena.remove(a);
+
+    arena.par_extend((0..1000i32).into_par_iter());
+    assert_eq!(arena.len(), 1001);
+    assert_eq!(arena[a], 0);
+    assert_eq!(arena[b], -2);
+    for i in 1..1000 {
+        assert_eq!(arena[i + 1], i as i32);
+    }
+}
+
+#[test]
+fn par_retain() {
+    let mut arena: Arena<usize> = (0..1000).collect();
+    arena.remove(10);
+    let mut sequential = arena.clone();
+
+    arena.par_retain(|index, value| {
+        assert_eq!(index, *value);
+        *value += 1;
+        *value % 2 == 0
+    });
+    sequential.retain(|_, value| {
+        *value += 1;
+        *value % 2 == 0
+    });
+
+    assert_eq!(arena.len(), 500);
+    assert_eq!(arena.get(8), None);
+    assert_eq!(arena.get(9), Some(&10));
+    assert_eq!(arena.get(10), None);
+    assert!(arena.iter().eq(sequential.iter()));
+    assert_eq!(arena.insert(0), sequential.insert(0));
+}
+
+#[test]
+fn par_drop() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut arena: Arena<_> = (0..1000).map(|_| Counted(drops.clone())).collect();
+    for i in (0..1000).step_by(3) {
+        arena.remove(i);
+    }
+    assert_eq!(drops.load(Ordering::SeqCst), 334);
+
+    arena.par_drop();
+    assert_eq!(drops.load(Ordering::SeqCst), 1000);
+}